@@ -0,0 +1,122 @@
+use std::{path::Path, pin::Pin};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    error::Error,
+    http::{HttpClient, HttpRequest},
+    Client, Provider,
+};
+
+pub mod speech;
+pub mod transcription;
+
+pub use speech::*;
+pub use transcription::*;
+
+/// The audio endpoints: speech, transcriptions and translations. They are also served by local OpenAI-compatible servers such as faster-whisper-server and LocalAI.
+#[derive(Debug, Clone)]
+pub struct Audio<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Audio<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self { client }
+    }
+
+    /// Transcribes audio into the input language.
+    pub async fn transcribe(&self, request: TranscriptionRequest) -> Result<Transcription, Error> {
+        self.send("/audio/transcriptions", request).await
+    }
+
+    /// Translates audio into English. `language` and `timestamp_granularities` are ignored.
+    pub async fn translate(
+        &self,
+        mut request: TranscriptionRequest,
+    ) -> Result<Transcription, Error> {
+        request.language = None;
+        request.timestamp_granularities = None;
+        self.send("/audio/translations", request).await
+    }
+
+    /// Generates audio from text and returns it once fully downloaded.
+    pub async fn speech(&self, request: SpeechRequest) -> Result<Bytes, Error> {
+        self.client
+            .http_client
+            .request_bytes(HttpRequest::post("/audio/speech").with_json(request)?)
+            .await
+    }
+
+    /// Generates audio from text and streams it while it downloads, e.g. to pipe it to a player.
+    pub async fn speech_stream(
+        &self,
+        request: SpeechRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>, Error> {
+        self.client
+            .http_client
+            .send_stream(HttpRequest::post("/audio/speech").with_json(request)?)
+            .await
+    }
+
+    /// Generates audio from text and writes it to a file while it downloads.
+    pub async fn speech_to_file(
+        &self,
+        request: SpeechRequest,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let mut stream = self.speech_stream(request).await?;
+        let mut file = tokio::fs::File::create(path).await?;
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        path: &str,
+        request: TranscriptionRequest,
+    ) -> Result<Transcription, Error> {
+        let is_text = request
+            .response_format
+            .is_some_and(|response_format| response_format.is_text());
+        let request = HttpRequest::post(path).with_multipart(request.into_form().await?);
+        let response = self
+            .client
+            .http_client
+            .send(request)
+            .await?
+            .error_for_status()?;
+        match is_text {
+            true => Ok(Transcription {
+                text: response.text(),
+                ..Default::default()
+            }),
+            false => response.json(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcription_works() {
+        let json = r#"{"task":"transcribe","language":"english","duration":8.47,"text":"The beach was a popular spot.","segments":[{"id":0,"seek":0,"start":0.0,"end":3.32,"text":" The beach was a popular spot.","tokens":[50364,440],"temperature":0.0,"avg_logprob":-0.28,"compression_ratio":1.23,"no_speech_prob":0.01}],"words":[{"word":"The","start":0.0,"end":0.24}]}"#;
+        let transcription: Transcription = serde_json::from_str(json).unwrap();
+        assert_eq!(transcription.segments.unwrap()[0].end, 3.32);
+        assert_eq!(transcription.words.unwrap()[0].word, "The");
+
+        let request = TranscriptionRequest::new("whisper-1", "audio.mp3")
+            .with_timestamp_granularities([TimestampGranularity::Word]);
+        assert_eq!(
+            request.response_format,
+            Some(AudioResponseFormat::VerboseJson)
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ChatAudioFormat, ChatAudioVoice};
+
+/// A request to `/audio/speech`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpeechRequest {
+    /// One of the TTS models, e.g. `tts-1`, `tts-1-hd` or `gpt-4o-mini-tts`.
+    pub model: String,
+
+    /// The text to generate audio for. The maximum length is 4096 characters.
+    pub input: String,
+
+    /// The voice to use. Local servers accept their own voice names through [`ChatAudioVoice::Other`].
+    pub voice: ChatAudioVoice,
+
+    /// Control the voice with additional instructions. Does not work with `tts-1` or `tts-1-hd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// The format of the audio: `mp3` (default), `opus`, `aac`, `flac`, `wav` or `pcm`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ChatAudioFormat>,
+
+    /// The speed of the generated audio, from 0.25 to 4.0. Defaults to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl SpeechRequest {
+    pub fn new(model: impl Into<String>, input: impl Into<String>, voice: ChatAudioVoice) -> Self {
+        Self {
+            model: model.into(),
+            input: input.into(),
+            voice,
+            instructions: None,
+            response_format: None,
+            speed: None,
+        }
+    }
+
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    pub fn with_response_format(mut self, response_format: ChatAudioFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speech_request_works() {
+        let request = SpeechRequest::new(
+            "kokoro",
+            "Hello world!",
+            ChatAudioVoice::Other("af_bella".into()),
+        )
+        .with_response_format(ChatAudioFormat::Pcm);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "model": "kokoro",
+                "input": "Hello world!",
+                "voice": "af_bella",
+                "response_format": "pcm"
+            })
+        );
+        let voice: ChatAudioVoice = serde_json::from_str(r#""nova""#).unwrap();
+        assert_eq!(voice, ChatAudioVoice::Nova);
+    }
+}
@@ -0,0 +1,180 @@
+use reqwest::multipart::Form;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, files::FileInput};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioResponseFormat {
+    Json,
+    Text,
+    Srt,
+    VerboseJson,
+    Vtt,
+}
+
+impl AudioResponseFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioResponseFormat::Json => "json",
+            AudioResponseFormat::Text => "text",
+            AudioResponseFormat::Srt => "srt",
+            AudioResponseFormat::VerboseJson => "verbose_json",
+            AudioResponseFormat::Vtt => "vtt",
+        }
+    }
+
+    /// Whether the response is plain text instead of JSON.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            AudioResponseFormat::Text | AudioResponseFormat::Srt | AudioResponseFormat::Vtt
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampGranularity {
+    Word,
+    Segment,
+}
+
+impl TimestampGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimestampGranularity::Word => "word",
+            TimestampGranularity::Segment => "segment",
+        }
+    }
+}
+
+/// A request to `/audio/transcriptions`, or to `/audio/translations` which translates into English and ignores `language` and `timestamp_granularities`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptionRequest {
+    /// The audio file, in one of these formats: flac, mp3, mp4, mpeg, mpga, m4a, ogg, wav, or webm.
+    pub file: FileInput,
+
+    /// ID of the model to use, e.g. `whisper-1`.
+    pub model: String,
+
+    /// The language of the input audio in ISO-639-1 format. Supplying it improves accuracy and latency.
+    pub language: Option<String>,
+
+    /// An optional text to guide the model's style or continue a previous audio segment. The prompt should match the audio language.
+    pub prompt: Option<String>,
+
+    /// The sampling temperature, between 0 and 1.
+    pub temperature: Option<f32>,
+
+    pub response_format: Option<AudioResponseFormat>,
+
+    /// The timestamp granularities to populate. `response_format` must be `verbose_json`.
+    pub timestamp_granularities: Option<Vec<TimestampGranularity>>,
+}
+
+impl TranscriptionRequest {
+    pub fn new(model: impl Into<String>, file: impl Into<FileInput>) -> Self {
+        Self {
+            file: file.into(),
+            model: model.into(),
+            language: None,
+            prompt: None,
+            temperature: None,
+            response_format: None,
+            timestamp_granularities: None,
+        }
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_response_format(mut self, response_format: AudioResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    /// Sets the granularities and the `verbose_json` response format they require.
+    pub fn with_timestamp_granularities(
+        mut self,
+        timestamp_granularities: impl IntoIterator<Item = TimestampGranularity>,
+    ) -> Self {
+        self.timestamp_granularities = Some(timestamp_granularities.into_iter().collect());
+        self.response_format = Some(AudioResponseFormat::VerboseJson);
+        self
+    }
+
+    pub(crate) async fn into_form(self) -> Result<Form, Error> {
+        let mut form = Form::new()
+            .part("file", self.file.into_part().await?)
+            .text("model", self.model);
+        if let Some(language) = self.language {
+            form = form.text("language", language);
+        }
+        if let Some(prompt) = self.prompt {
+            form = form.text("prompt", prompt);
+        }
+        if let Some(temperature) = self.temperature {
+            form = form.text("temperature", temperature.to_string());
+        }
+        if let Some(response_format) = self.response_format {
+            form = form.text("response_format", response_format.as_str());
+        }
+        for granularity in self.timestamp_granularities.unwrap_or_default() {
+            form = form.text("timestamp_granularities[]", granularity.as_str());
+        }
+        Ok(form)
+    }
+}
+
+/// A transcription or translation. With the `text`, `srt` and `vtt` response formats, `text` holds the whole response body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Transcription {
+    pub text: String,
+
+    /// The detected language. Only with `verbose_json`.
+    pub language: Option<String>,
+
+    /// The duration of the input audio in seconds. Only with `verbose_json`.
+    pub duration: Option<f64>,
+
+    pub segments: Option<Vec<TranscriptionSegment>>,
+
+    pub words: Option<Vec<TranscriptionWord>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptionSegment {
+    pub id: u32,
+    pub seek: Option<u32>,
+    /// Start time of the segment in seconds.
+    pub start: f64,
+    /// End time of the segment in seconds.
+    pub end: f64,
+    pub text: String,
+    pub tokens: Option<Vec<u32>>,
+    pub temperature: Option<f64>,
+    pub avg_logprob: Option<f64>,
+    pub compression_ratio: Option<f64>,
+    /// The probability of no speech in the segment. A segment with a high value and a low `avg_logprob` is likely silent.
+    pub no_speech_prob: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptionWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{error::Error, ChatRequest};
+
+use super::BatchEndpoint;
+
+/// Reads one JSON value per line, skipping blank lines.
+pub fn read_jsonl<T: DeserializeOwned>(
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<T, Error>> {
+    reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map_err(Error::from)),
+        Err(e) => Some(Err(e.into())),
+    })
+}
+
+/// Writes one JSON value per line.
+pub fn write_jsonl<T: Serialize>(
+    mut writer: impl Write,
+    items: impl IntoIterator<Item = T>,
+) -> Result<(), Error> {
+    for item in items {
+        serde_json::to_writer(&mut writer, &item)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// One request of a batch input file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchRequestLine<T = ChatRequest> {
+    /// A developer-provided ID used to match outputs to inputs. Must be unique within a batch.
+    pub custom_id: String,
+
+    /// The HTTP method, currently only `POST`.
+    pub method: String,
+
+    /// The API path, e.g. `/v1/chat/completions`.
+    pub url: String,
+
+    pub body: T,
+}
+
+impl<T> BatchRequestLine<T> {
+    pub fn new(custom_id: impl Into<String>, endpoint: BatchEndpoint, body: T) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            method: "POST".into(),
+            url: endpoint.as_str().into(),
+            body,
+        }
+    }
+}
+
+/// One line of a batch output or error file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchResponseLine {
+    pub id: Option<String>,
+    pub custom_id: String,
+    pub response: Option<BatchLineResponse>,
+    pub error: Option<BatchLineError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchLineResponse {
+    pub status_code: u16,
+    pub request_id: Option<String>,
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchLineError {
+    pub code: Option<String>,
+    pub message: Option<String>,
+}
+
+impl BatchResponseLine {
+    /// Parses the response body, e.g. into a [`crate::ChatResponse`]. Failed requests return [`Error::Batch`].
+    pub fn parse<T: DeserializeOwned>(self) -> Result<T, Error> {
+        if let Some(error) = self.error {
+            return Err(Error::Batch(format!(
+                "custom_id = {}, code = {:?}, message = {:?}",
+                self.custom_id, error.code, error.message
+            )));
+        }
+        let Some(response) = self.response else {
+            return Err(Error::Batch(format!(
+                "custom_id = {}, missing response",
+                self.custom_id
+            )));
+        };
+        if !(200..300).contains(&response.status_code) {
+            return Err(Error::Batch(format!(
+                "custom_id = {}, status code = {}, body = {}",
+                self.custom_id, response.status_code, response.body
+            )));
+        }
+        Ok(serde_json::from_value(response.body)?)
+    }
+}
+
+/// Parses the lines of batch output and error files into their bodies keyed by `custom_id`.
+pub fn parse_results<T: DeserializeOwned>(
+    jsonl: &[u8],
+) -> Result<HashMap<String, Result<T, Error>>, Error> {
+    read_jsonl::<BatchResponseLine>(jsonl)
+        .map(|line| line.map(|line| (line.custom_id.clone(), line.parse())))
+        .collect()
+}
+
+/// The requests of a batch input file, all sent to the same endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchFile<T = ChatRequest> {
+    pub endpoint: BatchEndpoint,
+    pub requests: Vec<BatchRequestLine<T>>,
+}
+
+impl Default for BatchFile {
+    fn default() -> Self {
+        Self::new(BatchEndpoint::ChatCompletions)
+    }
+}
+
+impl<T> BatchFile<T> {
+    pub fn new(endpoint: BatchEndpoint) -> Self {
+        Self {
+            endpoint,
+            requests: vec![],
+        }
+    }
+
+    pub fn push(&mut self, custom_id: impl Into<String>, body: T) {
+        self.requests
+            .push(BatchRequestLine::new(custom_id, self.endpoint, body));
+    }
+
+    pub fn with_request(mut self, custom_id: impl Into<String>, body: T) -> Self {
+        self.push(custom_id, body);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+impl<T: Serialize> BatchFile<T> {
+    pub fn to_jsonl(&self) -> Result<Vec<u8>, Error> {
+        let mut jsonl = vec![];
+        write_jsonl(&mut jsonl, &self.requests)?;
+        Ok(jsonl)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let file = std::fs::File::create(path)?;
+        write_jsonl(BufWriter::new(file), &self.requests)
+    }
+}
+
+impl<T: DeserializeOwned> BatchFile<T> {
+    /// Reads a batch input file. The endpoint is taken from the first request, defaulting to chat completions.
+    pub fn from_jsonl(reader: impl BufRead) -> Result<Self, Error> {
+        let requests = read_jsonl::<BatchRequestLine<T>>(reader).collect::<Result<Vec<_>, _>>()?;
+        let endpoint = match requests.first() {
+            Some(request) => serde_json::from_value(request.url.clone().into())?,
+            None => BatchEndpoint::ChatCompletions,
+        };
+        Ok(Self { endpoint, requests })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_jsonl(BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ChatResponse;
+
+    use super::*;
+
+    #[test]
+    fn batch_file_works() {
+        let file = BatchFile::default()
+            .with_request("request-1", ChatRequest::new("gpt-4o-mini", vec![]))
+            .with_request("request-2", ChatRequest::new("gpt-4o-mini", vec![]));
+        let jsonl = file.to_jsonl().unwrap();
+        assert_eq!(jsonl.iter().filter(|b| **b == b'\n').count(), 2);
+        assert!(String::from_utf8_lossy(&jsonl).contains(r#""url":"/v1/chat/completions""#));
+        assert_eq!(BatchFile::from_jsonl(jsonl.as_slice()).unwrap(), file);
+    }
+
+    #[test]
+    fn parse_results_works() {
+        let jsonl = r#"{"id":"batch_req_1","custom_id":"request-1","response":{"status_code":200,"request_id":"req_1","body":{"id":"chatcmpl-1","object":"chat.completion","created":1711475054,"model":"gpt-4o-mini","choices":[{"index":0,"message":{"role":"assistant","content":"Hello."},"finish_reason":"stop"}]}},"error":null}
+
+{"id":"batch_req_2","custom_id":"request-2","response":null,"error":{"code":"batch_expired","message":"This request could not be executed before the completion window expired."}}
+"#;
+        let results = parse_results::<ChatResponse>(jsonl.as_bytes()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results["request-1"].is_ok());
+        assert!(matches!(results["request-2"], Err(Error::Batch(_))));
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::Error,
+    http::HttpClient,
+    types::{FileObject, FilePurpose},
+    Client, Provider,
+};
+
+pub mod jsonl;
+pub mod types;
+
+pub use jsonl::*;
+pub use types::*;
+
+/// The OpenAI Batch API: asynchronous groups of requests at a discount, processed within 24 hours.
+#[derive(Debug, Clone)]
+pub struct Batches<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Batches<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self { client }
+    }
+
+    /// Uploads a batch input file with purpose `batch`.
+    pub async fn upload<T: Serialize>(&self, file: &BatchFile<T>) -> Result<FileObject, Error> {
+        self.client
+            .files()
+            .upload_bytes("batch.jsonl", file.to_jsonl()?, FilePurpose::Batch)
+            .await
+    }
+
+    pub async fn create(&self, request: CreateBatchRequest) -> Result<Batch, Error> {
+        self.client.http_client.post("/batches", request).await
+    }
+
+    /// Uploads the file and creates a batch from it.
+    pub async fn submit<T: Serialize>(&self, file: &BatchFile<T>) -> Result<Batch, Error> {
+        let input = self.upload(file).await?;
+        self.create(CreateBatchRequest::new(input.id, file.endpoint))
+            .await
+    }
+
+    pub async fn retrieve(&self, batch_id: &str) -> Result<Batch, Error> {
+        self.client
+            .http_client
+            .get(&format!("/batches/{batch_id}"), &[])
+            .await
+    }
+
+    /// Lists batches, newest first. `after` is the ID of the last batch of the previous page.
+    pub async fn list(&self, after: Option<&str>, limit: Option<u32>) -> Result<BatchList, Error> {
+        let limit = limit.map(|limit| limit.to_string());
+        let mut query = vec![];
+        if let Some(after) = after {
+            query.push(("after", after));
+        }
+        if let Some(limit) = &limit {
+            query.push(("limit", limit.as_str()));
+        }
+        self.client.http_client.get("/batches", &query).await
+    }
+
+    /// Cancels an in-progress batch. The batch is `cancelling` for up to 10 minutes before it is `cancelled`.
+    pub async fn cancel(&self, batch_id: &str) -> Result<Batch, Error> {
+        self.client
+            .http_client
+            .post(
+                &format!("/batches/{batch_id}/cancel"),
+                serde_json::json!({}),
+            )
+            .await
+    }
+
+    /// Polls the batch every `interval` until it reaches a terminal status.
+    pub async fn wait(&self, batch_id: &str, interval: Duration) -> Result<Batch, Error> {
+        loop {
+            let batch = self.retrieve(batch_id).await?;
+            if batch.status.is_terminal() {
+                return Ok(batch);
+            }
+            tracing::debug!("batch {batch_id} is {:?}", batch.status);
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Downloads the output and error files of a batch and parses the response bodies keyed by `custom_id`.
+    pub async fn results<T: DeserializeOwned>(
+        &self,
+        batch: &Batch,
+    ) -> Result<HashMap<String, Result<T, Error>>, Error> {
+        let mut results = HashMap::new();
+        for file_id in [&batch.output_file_id, &batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            results.extend(parse_results(&self.client.files().content(file_id).await?)?);
+        }
+        Ok(results)
+    }
+
+    /// Like [`Batches::results`] for a batch of chat requests.
+    pub async fn chat_results(
+        &self,
+        batch: &Batch,
+    ) -> Result<HashMap<String, Result<crate::ChatResponse, Error>>, Error> {
+        self.results(batch).await
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The endpoint used by all requests of a batch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BatchEndpoint {
+    #[serde(rename = "/v1/chat/completions")]
+    ChatCompletions,
+    #[serde(rename = "/v1/completions")]
+    Completions,
+    #[serde(rename = "/v1/embeddings")]
+    Embeddings,
+}
+
+impl BatchEndpoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchEndpoint::ChatCompletions => "/v1/chat/completions",
+            BatchEndpoint::Completions => "/v1/completions",
+            BatchEndpoint::Embeddings => "/v1/embeddings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Validating,
+    Failed,
+    InProgress,
+    Finalizing,
+    Completed,
+    Expired,
+    Cancelling,
+    Cancelled,
+}
+
+impl BatchStatus {
+    /// Whether the batch will not change anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            BatchStatus::Failed
+                | BatchStatus::Completed
+                | BatchStatus::Expired
+                | BatchStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreateBatchRequest {
+    /// The ID of an uploaded JSONL file with purpose `batch`.
+    pub input_file_id: String,
+
+    pub endpoint: BatchEndpoint,
+
+    /// The time frame within which the batch should be processed. Currently only `24h` is supported.
+    pub completion_window: String,
+
+    /// Up to 16 key-value pairs attached to the batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateBatchRequest {
+    pub fn new(input_file_id: impl Into<String>, endpoint: BatchEndpoint) -> Self {
+        Self {
+            input_file_id: input_file_id.into(),
+            endpoint,
+            completion_window: "24h".into(),
+            metadata: None,
+        }
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .get_or_insert_with(Default::default)
+            .insert(key.into(), value.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Batch {
+    pub id: String,
+
+    /// The object type, which is always `batch`.
+    pub object: Option<String>,
+
+    pub endpoint: Option<String>,
+
+    pub errors: Option<BatchErrors>,
+
+    /// The ID of the input file for the batch.
+    pub input_file_id: Option<String>,
+
+    pub completion_window: Option<String>,
+
+    pub status: BatchStatus,
+
+    /// The ID of the file containing the outputs of successfully executed requests.
+    pub output_file_id: Option<String>,
+
+    /// The ID of the file containing the outputs of requests with errors.
+    pub error_file_id: Option<String>,
+
+    /// The Unix timestamp (in seconds) for when the batch was created.
+    pub created_at: Option<u64>,
+    pub in_progress_at: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub finalizing_at: Option<u64>,
+    pub completed_at: Option<u64>,
+    pub failed_at: Option<u64>,
+    pub expired_at: Option<u64>,
+    pub cancelling_at: Option<u64>,
+    pub cancelled_at: Option<u64>,
+
+    pub request_counts: Option<BatchRequestCounts>,
+
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchErrors {
+    pub object: Option<String>,
+    pub data: Vec<BatchError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchError {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub param: Option<String>,
+    /// The line number of the input file where the error occurred.
+    pub line: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchRequestCounts {
+    pub total: u32,
+    pub completed: u32,
+    pub failed: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchList {
+    pub object: Option<String>,
+    pub data: Vec<Batch>,
+    pub first_id: Option<String>,
+    pub last_id: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
@@ -0,0 +1,458 @@
+use std::{fmt::Debug, pin::Pin, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::{error::Error, http::HttpClient, Provider};
+
+use super::Chat;
+
+/// Progress of a batch, passed to [`BatchOptions::on_progress`] after every finished item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    pub completed: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// The number of items, if known from the iterator's size hint.
+    pub total: Option<usize>,
+}
+
+type ProgressCallback = Arc<dyn Fn(&BatchProgress) + Send + Sync>;
+
+/// Options for [`Chat::create_many_with`] and [`Chat::create_many_stream`].
+#[derive(Clone)]
+pub struct BatchOptions {
+    /// The maximum number of requests in flight.
+    pub concurrency: usize,
+
+    /// How many times a failed request is retried. Only errors for which [`Error::is_retryable`] is true are retried.
+    pub retries: u32,
+
+    /// The delay before the first retry, doubled for every further retry.
+    pub backoff: Duration,
+
+    /// Yield results in input order instead of completion order.
+    pub ordered: bool,
+
+    pub on_progress: Option<ProgressCallback>,
+
+    /// Stops the batch: in-flight requests are aborted and no further requests are sent.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Debug for BatchOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchOptions")
+            .field("concurrency", &self.concurrency)
+            .field("retries", &self.retries)
+            .field("backoff", &self.backoff)
+            .field("ordered", &self.ordered)
+            .field("cancellation", &self.cancellation)
+            .finish()
+    }
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            retries: 2,
+            backoff: Duration::from_millis(500),
+            ordered: false,
+            on_progress: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl BatchOptions {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn ordered(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&BatchProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+}
+
+/// The outcome of one request of a batch.
+#[derive(Debug)]
+pub struct BatchItem<R> {
+    /// The position of the request in the input.
+    pub index: usize,
+
+    /// The number of attempts, including retries.
+    pub attempts: u32,
+
+    pub result: Result<R, Error>,
+}
+
+/// The outcome of a whole batch, in input order.
+#[derive(Debug)]
+pub struct BatchResult<R> {
+    /// One result per request. Requests that were not sent because the batch was cancelled fail with [`Error::Cancelled`].
+    pub results: Vec<Result<R, Error>>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl<R> BatchResult<R> {
+    pub fn is_success(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// The successful responses with their input position.
+    pub fn successes(&self) -> impl Iterator<Item = (usize, &R)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.as_ref().ok().map(|r| (index, r)))
+    }
+
+    /// The errors with their input position.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &Error)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.as_ref().err().map(|e| (index, e)))
+    }
+}
+
+impl<P, H> Chat<'_, P, H>
+where
+    P: Provider,
+    P::ChatRequest: Clone + Send + Sync,
+    P::ChatResponse: Send,
+    H: HttpClient,
+{
+    /// Sends many requests with at most `concurrency` in flight and collects the results in input order. A failed request does not stop the others.
+    pub async fn create_many<I, T>(
+        &self,
+        requests: I,
+        concurrency: usize,
+    ) -> BatchResult<P::ChatResponse>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send,
+        T: TryInto<P::ChatRequest> + Send,
+        T::Error: Debug,
+    {
+        self.create_many_with(requests, BatchOptions::new(concurrency))
+            .await
+    }
+
+    /// Like [`Chat::create_many`] with retries, progress and cancellation configured by `options`.
+    pub async fn create_many_with<I, T>(
+        &self,
+        requests: I,
+        options: BatchOptions,
+    ) -> BatchResult<P::ChatResponse>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send,
+        T: TryInto<P::ChatRequest> + Send,
+        T::Error: Debug,
+    {
+        let requests: Vec<T> = requests.into_iter().collect();
+        let mut results: Vec<Option<Result<P::ChatResponse, Error>>> =
+            (0..requests.len()).map(|_| None).collect();
+        let mut stream = self.create_many_stream(requests, options);
+        while let Some(item) = stream.next().await {
+            results[item.index] = Some(item.result);
+        }
+
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(Error::Cancelled)))
+            .collect();
+        let succeeded = results.iter().filter(|result| result.is_ok()).count();
+        BatchResult {
+            failed: results.len() - succeeded,
+            succeeded,
+            results,
+        }
+    }
+
+    /// Sends many requests and yields each result as soon as it is available (or in input order when [`BatchOptions::ordered`] is set).
+    pub fn create_many_stream<'s, I, T>(
+        &'s self,
+        requests: I,
+        options: BatchOptions,
+    ) -> Pin<Box<dyn Stream<Item = BatchItem<P::ChatResponse>> + Send + 's>>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send + 's,
+        T: TryInto<P::ChatRequest> + Send + 's,
+        T::Error: Debug,
+    {
+        let requests = requests.into_iter();
+        let total = match requests.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        };
+        let retries = options.retries;
+        let backoff = options.backoff;
+        let items =
+            futures::stream::iter(requests.enumerate()).map(move |(index, request)| async move {
+                let request: P::ChatRequest = match request.try_into() {
+                    Ok(request) => request,
+                    Err(e) => {
+                        return BatchItem {
+                            index,
+                            attempts: 0,
+                            result: Err(Error::InvalidArgument(format!(
+                                "Failed to convert to ChatRequest. Error = {e:?}"
+                            ))),
+                        }
+                    }
+                };
+                let mut attempts = 0;
+                loop {
+                    attempts += 1;
+                    let result = self.create(request.clone()).await;
+                    match result {
+                        Err(e) if e.is_retryable() && attempts <= retries => {
+                            let delay = backoff * 2u32.saturating_pow(attempts - 1);
+                            tracing::debug!(
+                                "batch item {index} failed, retrying in {delay:?}: {e}"
+                            );
+                            tokio::time::sleep(delay).await;
+                        }
+                        result => {
+                            return BatchItem {
+                                index,
+                                attempts,
+                                result,
+                            }
+                        }
+                    }
+                }
+            });
+
+        let concurrency = options.concurrency.max(1);
+        let items: Pin<Box<dyn Stream<Item = BatchItem<P::ChatResponse>> + Send + 's>> =
+            match options.ordered {
+                true => Box::pin(items.buffered(concurrency)),
+                false => Box::pin(items.buffer_unordered(concurrency)),
+            };
+        let items = match options.cancellation {
+            Some(cancellation) => Box::pin(items.take_until(cancellation.cancelled_owned())),
+            None => items,
+        };
+
+        let Some(on_progress) = options.on_progress else {
+            return items;
+        };
+        let mut completed = 0;
+        let mut succeeded = 0;
+        Box::pin(items.inspect(move |item| {
+            completed += 1;
+            succeeded += usize::from(item.result.is_ok());
+            on_progress(&BatchProgress {
+                completed,
+                succeeded,
+                failed: completed - succeeded,
+                total,
+            });
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+    };
+
+    use bytes::Bytes;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        http::{HttpBody, HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        providers::OpenAIConfig,
+        rate_limit::RateLimiter,
+        ChatMessage, ChatRequest, Client, OpenAIProvider,
+    };
+
+    /// Answers a prompt `<status>:<failures>:<delay ms>` with `status` for the first `failures` attempts, then echoes the prompt after `delay` ms.
+    #[derive(Debug, Clone, Default)]
+    struct MockHttpClient {
+        attempts: Arc<Mutex<HashMap<String, u32>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let HttpBody::Json(body) = &request.body else {
+                return Err(Error::InvalidArgument("expected a JSON body".into()));
+            };
+            let prompt = body["messages"][0]["content"].as_str().unwrap().to_string();
+            let parts: Vec<u64> = prompt.split(':').map(|p| p.parse().unwrap()).collect();
+            let attempts = {
+                let mut attempts = self.attempts.lock().unwrap();
+                let attempt = attempts.entry(prompt.clone()).or_default();
+                *attempt += 1;
+                *attempt
+            };
+            tokio::time::sleep(Duration::from_millis(parts[2])).await;
+            let (status, body) = match u64::from(attempts) <= parts[1] {
+                true => (parts[0] as u16, json!({"error": {"message": "failed"}}).to_string()),
+                false => (
+                    200,
+                    json!({
+                        "id": prompt,
+                        "choices": [{"index": 0, "message": {"role": "assistant", "content": prompt}}]
+                    })
+                    .to_string(),
+                ),
+            };
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::from_u16(status).unwrap(),
+                headers: HeaderMap::new(),
+                body: Bytes::from(body),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not mocked".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    fn client() -> Client<OpenAIProvider, MockHttpClient> {
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        Client::with_args(OpenAIProvider::new(config), MockHttpClient::default())
+    }
+
+    fn requests(prompts: &[&str]) -> Vec<ChatRequest> {
+        prompts
+            .iter()
+            .map(|prompt| ChatRequest::new("gpt-4o-mini", vec![ChatMessage::user(*prompt)]))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn batch_retries_work() {
+        let client = client();
+        let options = BatchOptions::new(4)
+            .with_retries(2)
+            .with_backoff(Duration::from_millis(1));
+        let items: Vec<_> = client
+            .chat()
+            .create_many_stream(requests(&["429:2:0", "500:3:0", "400:1:0"]), options)
+            .collect()
+            .await;
+        let attempts: HashMap<usize, (u32, bool)> = items
+            .iter()
+            .map(|item| (item.index, (item.attempts, item.result.is_ok())))
+            .collect();
+        assert_eq!(attempts[&0], (3, true));
+        assert_eq!(attempts[&1], (3, false));
+        assert_eq!(attempts[&2], (1, false));
+    }
+
+    #[tokio::test]
+    async fn batch_order_and_progress_work() {
+        let client = client();
+        let prompts = ["200:0:60", "200:0:0", "200:0:30"];
+
+        let indices: Vec<_> = client
+            .chat()
+            .create_many_stream(requests(&prompts), BatchOptions::new(3))
+            .map(|item| item.index)
+            .collect()
+            .await;
+        assert_eq!(indices, vec![1, 2, 0]);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let options = BatchOptions::new(3).ordered().on_progress(move |progress| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(progress.total, Some(3));
+        });
+        let items: Vec<_> = client
+            .chat()
+            .create_many_stream(requests(&prompts), options)
+            .collect()
+            .await;
+        assert_eq!(
+            items.iter().map(|item| item.index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let result = client.chat().create_many(requests(&prompts), 2).await;
+        assert!(result.is_success());
+        let ids: Vec<_> = result
+            .successes()
+            .map(|(_, response)| response.id.clone())
+            .collect();
+        assert_eq!(ids, prompts.map(|prompt| Some(prompt.to_string())));
+    }
+
+    #[tokio::test]
+    async fn batch_cancellation_works() {
+        let client = client();
+        let cancellation = CancellationToken::new();
+        let token = cancellation.clone();
+        let options = BatchOptions::new(1)
+            .with_cancellation(cancellation)
+            .on_progress(move |_| token.cancel());
+        let result = client
+            .chat()
+            .create_many_with(requests(&["200:0:0", "200:0:0", "200:0:0"]), options)
+            .await;
+        assert_eq!(result.succeeded, 1);
+        assert!(matches!(result.results[1], Err(Error::Cancelled)));
+        assert!(matches!(result.results[2], Err(Error::Cancelled)));
+    }
+}
@@ -1,13 +1,40 @@
 use std::fmt::Debug;
 use std::pin::Pin;
 
-use crate::{error::Error, http::HttpClient, request::Requestable, Client, Provider};
+use crate::{
+    error::Error,
+    http::{
+        stream::{cancellable, with_cancellation},
+        HttpClient, ResponseMeta,
+    },
+    moderations::{ModerationInput, ModerationStage},
+    request::Requestable,
+    response::Respondable,
+    Client, Provider,
+};
 
-use futures::Stream;
+use futures::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+pub mod batch;
+#[cfg(feature = "tower")]
+pub mod service;
+pub mod text;
+
+pub use batch::{BatchItem, BatchOptions, BatchProgress, BatchResult};
+#[cfg(feature = "tower")]
+pub use service::ChatService;
+pub use text::{
+    ChatStreamExt, ChunkBoundary, ChunkedTextStream, TeeResponse, TextChunker, TextStream,
+    TextStreamExt,
+};
 
 #[derive(Debug, Clone)]
 pub struct Chat<'c, P: Provider, H: HttpClient> {
     pub(crate) client: &'c Client<P, H>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) cancellation: Option<CancellationToken>,
 }
 
 impl<'c, P, H> Chat<'c, P, H>
@@ -16,10 +43,38 @@ where
     H: HttpClient,
 {
     pub fn new(client: &'c Client<P, H>) -> Self {
-        Self { client }
+        Self {
+            client,
+            tags: vec![],
+            cancellation: None,
+        }
+    }
+
+    /// Adds a caller-defined tag under which the usage of the following calls is aggregated by the client's usage tracker.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Cancels the following calls when the token is cancelled: pending calls fail and streams end with [`Error::Cancelled`], closing the connection.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
     }
 
     pub async fn create<T>(&self, request: T) -> Result<P::ChatResponse, Error>
+    where
+        T: TryInto<P::ChatRequest>,
+        T::Error: Debug,
+    {
+        Ok(self.create_with_meta(request).await?.0)
+    }
+
+    /// Like [`Chat::create`], with the status, headers, request id and timing of the response, and its raw body with [`Client::with_raw_body`].
+    pub async fn create_with_meta<T>(
+        &self,
+        request: T,
+    ) -> Result<(P::ChatResponse, ResponseMeta), Error>
     where
         T: TryInto<P::ChatRequest>,
         T::Error: Debug,
@@ -33,17 +88,39 @@ where
                 "When stream is true, use the client.create_stream function instead".into(),
             )),
             false => {
-                self.client
-                    .provider
-                    .chat(&self.client.http_client, request)
-                    .await
+                let instrumentation = self.client.instrument("chat", &request);
+                let result = self
+                    .send(request)
+                    .instrument(instrumentation.span().clone())
+                    .await;
+                instrumentation.finish(result.as_ref().map(|(response, _)| response));
+                result
             }
         }
     }
+
     pub async fn create_stream<T>(
         &self,
         request: T,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<P::ChatResponseStream, Error>> + Send>>, Error>
+    where
+        T: TryInto<P::ChatRequest>,
+        T::Error: Debug,
+    {
+        Ok(self.create_stream_with_meta(request).await?.0)
+    }
+
+    /// Like [`Chat::create_stream`], with the status, headers, request id and time to first byte of the response.
+    pub async fn create_stream_with_meta<T>(
+        &self,
+        request: T,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<P::ChatResponseStream, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    >
     where
         T: TryInto<P::ChatRequest>,
         T::Error: Debug,
@@ -57,11 +134,106 @@ where
                 "When stream is false, use the client.create function instead".into(),
             )),
             true => {
-                self.client
-                    .provider
-                    .chat_stream(&self.client.http_client, request)
-                    .await
+                let instrumentation = self.client.instrument("chat", &request);
+                let result = self
+                    .open_stream(request)
+                    .instrument(instrumentation.span().clone())
+                    .await;
+                match result {
+                    Ok((stream, meta)) => Ok((instrumentation.stream(stream), meta)),
+                    Err(e) => {
+                        instrumentation.fail(&e);
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        request: P::ChatRequest,
+    ) -> Result<(P::ChatResponse, ResponseMeta), Error> {
+        let recorder = self.client.usage_recorder(&self.tags);
+        let model = request.model().map(str::to_string);
+        recorder.check(model.as_deref())?;
+        if self.client.moderates(ModerationStage::Input) {
+            let input = request.moderation_input();
+            self.client.moderate(ModerationStage::Input, input).await?;
+        }
+        self.client.acquire(&request).await;
+        let (response, mut meta) = with_cancellation(
+            self.client
+                .provider
+                .chat_with_meta(&self.client.http_client, request),
+            self.cancellation.as_ref(),
+        )
+        .await?;
+        if !self.client.raw_body {
+            meta.body = None;
+        }
+        if let Some(usage) = response.usage() {
+            recorder.record(
+                response.model().or(model.as_deref()).unwrap_or_default(),
+                &usage,
+            );
+        }
+        if self.client.moderates(ModerationStage::Output) {
+            let output = response.text().map(ModerationInput::from);
+            self.client
+                .moderate(ModerationStage::Output, output)
+                .await?;
+        }
+        Ok((response, meta))
+    }
+
+    async fn open_stream(
+        &self,
+        mut request: P::ChatRequest,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<P::ChatResponseStream, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        let recorder = self.client.usage_recorder(&self.tags);
+        let model = request.model().map(str::to_string).unwrap_or_default();
+        recorder.check(Some(&model))?;
+        if recorder.is_enabled() {
+            // Usage is only sent on the last chunk when `stream_options.include_usage` is set.
+            request.include_usage();
+        }
+        if self.client.moderates(ModerationStage::Input) {
+            let input = request.moderation_input();
+            self.client.moderate(ModerationStage::Input, input).await?;
+        }
+        self.client.acquire(&request).await;
+        let (mut stream, meta) = with_cancellation(
+            self.client
+                .provider
+                .chat_stream_with_meta(&self.client.http_client, request),
+            self.cancellation.as_ref(),
+        )
+        .await?;
+        if let Some(cancellation) = &self.cancellation {
+            stream = cancellable(stream, cancellation.clone());
+        }
+        if recorder.is_enabled() {
+            stream = Box::pin(stream.inspect(move |chunk| {
+                if let Ok(chunk) = chunk {
+                    if let Some(usage) = chunk.usage() {
+                        recorder.record(chunk.model().unwrap_or(&model), &usage);
+                    }
+                }
+            }));
+        }
+        if let Some(guard) = &self.client.moderation_guard {
+            if guard.checks(ModerationStage::Output) {
+                let http_client = self.client.http_client.clone();
+                stream = guard.clone().screen_stream(http_client, stream);
             }
         }
+        Ok((stream, meta))
     }
 }
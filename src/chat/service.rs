@@ -0,0 +1,54 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tower::Service;
+
+use crate::{error::Error, http::HttpClient, Client, Provider};
+
+/// Chat calls as a [`tower::Service`], so they compose with tower layers and stacks.
+#[derive(Debug)]
+pub struct ChatService<P: Provider, H: HttpClient> {
+    client: Arc<Client<P, H>>,
+}
+
+impl<P: Provider, H: HttpClient> Clone for ChatService<P, H> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+        }
+    }
+}
+
+impl<P: Provider, H: HttpClient> ChatService<P, H> {
+    pub fn new(client: Client<P, H>) -> Self {
+        Self {
+            client: Arc::new(client),
+        }
+    }
+}
+
+impl<P, H> Service<P::ChatRequest> for ChatService<P, H>
+where
+    P: Provider + 'static,
+    P::ChatRequest: Send + Sync + 'static,
+    P::ChatResponse: Send + 'static,
+    H: HttpClient + 'static,
+{
+    type Response = P::ChatResponse;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<P::ChatResponse, Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: P::ChatRequest) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move { client.chat().create(request).await })
+    }
+}
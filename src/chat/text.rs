@@ -0,0 +1,345 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{channel::oneshot, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{error::Error, json::PartialJsonStream, ChatResponse, ChatResponseStream};
+
+/// Where [`TextChunker`] splits the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkBoundary {
+    /// After `.`, `!`, `?` or `…` followed by whitespace, or after `。`, `！` and `？`. Chunks are trimmed.
+    Sentence,
+    /// After `\n`. Chunks exclude the line ending.
+    Line,
+    /// After a blank line. Chunks are trimmed.
+    Paragraph,
+}
+
+/// Re-chunks text deltas into complete sentences, lines or paragraphs, e.g. for text-to-speech.
+#[derive(Debug, Clone)]
+pub struct TextChunker {
+    boundary: ChunkBoundary,
+    buffer: String,
+}
+
+impl TextChunker {
+    pub fn new(boundary: ChunkBoundary) -> Self {
+        Self {
+            boundary,
+            buffer: String::new(),
+        }
+    }
+
+    /// Appends a delta and returns the chunks it completes.
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.buffer.push_str(delta);
+        let mut chunks = vec![];
+        while let Some(end) = self.boundary_end() {
+            let rest = self.buffer.split_off(end);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            if let Some(chunk) = self.clean(chunk) {
+                chunks.push(chunk);
+            }
+        }
+        chunks
+    }
+
+    /// Returns the remaining text once the stream has ended.
+    pub fn finish(&mut self) -> Option<String> {
+        let chunk = std::mem::take(&mut self.buffer);
+        match self.boundary {
+            ChunkBoundary::Line if chunk.is_empty() => None,
+            _ => self.clean(chunk),
+        }
+    }
+
+    /// The byte offset after the first complete chunk in the buffer.
+    fn boundary_end(&self) -> Option<usize> {
+        match self.boundary {
+            ChunkBoundary::Line => self.buffer.find('\n').map(|i| i + 1),
+            ChunkBoundary::Paragraph => self.buffer.find("\n\n").map(|i| i + 2),
+            ChunkBoundary::Sentence => {
+                let mut chars = self.buffer.char_indices().peekable();
+                while let Some((_, c)) = chars.next() {
+                    if matches!(c, '。' | '！' | '？') {
+                        return chars.peek().map(|(i, _)| *i);
+                    }
+                    if !matches!(c, '.' | '!' | '?' | '…') {
+                        continue;
+                    }
+                    // Keep closing quotes and brackets with the sentence, e.g. `"Done."`.
+                    while let Some((_, c)) = chars.peek() {
+                        if !matches!(
+                            c,
+                            '.' | '!' | '?' | '…' | '"' | '\'' | ')' | ']' | '”' | '’'
+                        ) {
+                            break;
+                        }
+                        chars.next();
+                    }
+                    // Wait for the next delta to tell `3.14` from the end of a sentence.
+                    match chars.peek() {
+                        Some((i, c)) if c.is_whitespace() => return Some(*i),
+                        Some(_) => continue,
+                        None => return None,
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn clean(&self, chunk: String) -> Option<String> {
+        match self.boundary {
+            ChunkBoundary::Line => {
+                let line = chunk.strip_suffix('\n').unwrap_or(&chunk);
+                Some(line.strip_suffix('\r').unwrap_or(line).to_string())
+            }
+            ChunkBoundary::Sentence | ChunkBoundary::Paragraph => {
+                let chunk = chunk.trim();
+                (!chunk.is_empty()).then(|| chunk.to_string())
+            }
+        }
+    }
+}
+
+/// A stream of the text deltas of the first choice of a chat stream, see [`ChatStreamExt::text`].
+///
+/// Role-only and empty chunks are skipped. A refusal is yielded as [`Error::Refusal`] once the stream ends.
+pub struct TextStream<S> {
+    inner: S,
+    response: Option<ChatResponse>,
+    refusal: String,
+    sender: Option<oneshot::Sender<ChatResponse>>,
+    done: bool,
+}
+
+impl<S> TextStream<S> {
+    fn new(inner: S) -> Self {
+        Self {
+            inner,
+            response: None,
+            refusal: String::new(),
+            sender: None,
+            done: false,
+        }
+    }
+}
+
+impl<S> Stream for TextStream<S>
+where
+    S: Stream<Item = Result<ChatResponseStream, Error>> + Unpin,
+{
+    type Item = Result<String, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    let delta = chunk
+                        .choices
+                        .iter()
+                        .find(|choice| choice.index.unwrap_or(0) == 0)
+                        .and_then(|choice| choice.delta.as_ref());
+                    let content = delta.and_then(|delta| delta.content.clone());
+                    if let Some(refusal) = delta.and_then(|delta| delta.refusal.as_deref()) {
+                        this.refusal.push_str(refusal);
+                    }
+                    if let Some(response) = this.response.as_mut() {
+                        response.merge_stream(chunk);
+                    }
+                    match content {
+                        Some(content) if !content.is_empty() => {
+                            return Poll::Ready(Some(Ok(content)))
+                        }
+                        _ => continue,
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if let (Some(sender), Some(response)) =
+                        (this.sender.take(), this.response.take())
+                    {
+                        let _ = sender.send(response);
+                    }
+                    if !this.refusal.is_empty() {
+                        let refusal = std::mem::take(&mut this.refusal);
+                        return Poll::Ready(Some(Err(Error::Refusal(refusal))));
+                    }
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The final response of [`ChatStreamExt::tee`]. It resolves once the text stream has ended, or fails with [`Error::Cancelled`] if the stream is dropped before.
+pub struct TeeResponse(oneshot::Receiver<ChatResponse>);
+
+impl Future for TeeResponse {
+    type Output = Result<ChatResponse, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0)
+            .poll(cx)
+            .map(|response| response.map_err(|_| Error::Cancelled))
+    }
+}
+
+/// A stream of complete chunks of text, see [`TextStreamExt::chunks`].
+pub struct ChunkedTextStream<S> {
+    inner: S,
+    chunker: TextChunker,
+    pending: VecDeque<String>,
+    done: bool,
+}
+
+impl<S> Stream for ChunkedTextStream<S>
+where
+    S: Stream<Item = Result<String, Error>> + Unpin,
+{
+    type Item = Result<String, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(chunk) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(chunk)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(delta))) => this.pending.extend(this.chunker.push(&delta)),
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    this.pending.extend(this.chunker.finish());
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Text adapters for the streams returned by [`super::Chat::create_stream`].
+pub trait ChatStreamExt: Stream<Item = Result<ChatResponseStream, Error>> + Sized {
+    /// Yields the text deltas of the first choice.
+    fn text(self) -> TextStream<Self> {
+        TextStream::new(self)
+    }
+
+    /// Yields the text deltas of the first choice, and accumulates all chunks into the final response.
+    fn tee(self) -> (TextStream<Self>, TeeResponse) {
+        let (sender, receiver) = oneshot::channel();
+        let mut stream = TextStream::new(self);
+        stream.response = Some(ChatResponse::from_stream([]));
+        stream.sender = Some(sender);
+        (stream, TeeResponse(receiver))
+    }
+}
+
+impl<S> ChatStreamExt for S where S: Stream<Item = Result<ChatResponseStream, Error>> {}
+
+/// Re-chunking adapters for streams of text deltas.
+pub trait TextStreamExt: Stream<Item = Result<String, Error>> + Sized {
+    fn chunks(self, boundary: ChunkBoundary) -> ChunkedTextStream<Self> {
+        ChunkedTextStream {
+            inner: self,
+            chunker: TextChunker::new(boundary),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn sentences(self) -> ChunkedTextStream<Self> {
+        self.chunks(ChunkBoundary::Sentence)
+    }
+
+    fn lines(self) -> ChunkedTextStream<Self> {
+        self.chunks(ChunkBoundary::Line)
+    }
+
+    fn paragraphs(self) -> ChunkedTextStream<Self> {
+        self.chunks(ChunkBoundary::Paragraph)
+    }
+
+    /// Yields best-effort snapshots of the JSON streamed so far, see [`crate::json::parse_partial`].
+    fn partial_json(self) -> PartialJsonStream<Self> {
+        PartialJsonStream::new(self)
+    }
+
+    /// Yields the snapshots of [`TextStreamExt::partial_json`] deserialized into `T`, whose fields should all be optional.
+    fn partial_json_as<T: DeserializeOwned>(self) -> PartialJsonStream<Self, T> {
+        PartialJsonStream::new(self)
+    }
+}
+
+impl<S> TextStreamExt for S where S: Stream<Item = Result<String, Error>> {}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+    use crate::response::Respondable;
+
+    fn chunk(delta: &str) -> Result<ChatResponseStream, Error> {
+        Ok(serde_json::from_str(&format!(
+            r#"{{"id":"1","model":"gpt-4o-mini","choices":[{{"index":0,"delta":{delta}}}]}}"#
+        ))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn text_stream_tee_works() {
+        let chunks = stream::iter([
+            chunk(r#"{"role":"assistant","content":""}"#),
+            chunk(r#"{"content":"Hi there. How"}"#),
+            chunk(r#"{"content":" are you? Pi is 3."}"#),
+            chunk(r#"{"content":"14!"}"#),
+        ]);
+        let (text, response) = chunks.tee();
+        let sentences: Vec<_> = text.sentences().map(Result::unwrap).collect().await;
+        assert_eq!(sentences, ["Hi there.", "How are you?", "Pi is 3.14!"]);
+        assert_eq!(
+            response.await.unwrap().text(),
+            Some("Hi there. How are you? Pi is 3.14!")
+        );
+    }
+
+    #[tokio::test]
+    async fn text_stream_refusal_works() {
+        let chunks = stream::iter([
+            chunk(r#"{"refusal":"I can't"}"#),
+            chunk(r#"{"refusal":" help."}"#),
+        ]);
+        let items: Vec<_> = chunks.text().collect().await;
+        assert!(matches!(&items[..], [Err(Error::Refusal(refusal))] if refusal == "I can't help."));
+    }
+
+    #[test]
+    fn text_chunker_works() {
+        let mut chunker = TextChunker::new(ChunkBoundary::Line);
+        assert_eq!(chunker.push("a\r\nb"), ["a"]);
+        assert_eq!(chunker.push("\n\nc"), ["b", ""]);
+        assert_eq!(chunker.finish().as_deref(), Some("c"));
+
+        let mut chunker = TextChunker::new(ChunkBoundary::Paragraph);
+        assert!(chunker.push("one\ntwo\n").is_empty());
+        assert_eq!(chunker.push("\nthree"), ["one\ntwo"]);
+        assert_eq!(chunker.finish().as_deref(), Some("three"));
+    }
+}
@@ -1,10 +1,22 @@
 use secrecy::SecretString;
 
 use crate::{
+    audio::Audio,
+    batches::Batches,
     chat::Chat,
     completions::Completions,
+    error::Error,
+    files::Files,
     http::{HttpClient, SimpleHttpClient},
+    images::Images,
+    middleware::{Middleware, MiddlewareStack},
+    moderations::{ModerationGuard, ModerationInput, ModerationStage, Moderations},
     providers::{openai::OpenAIProvider, OpenAIConfig, Provider},
+    rate_limit::RateLimiter,
+    request::Requestable,
+    responses::Responses,
+    telemetry::{Instrumentation, Telemetry},
+    usage::{Budgets, UsageRecorder, UsageTracker},
     RawProvider,
 };
 
@@ -14,15 +26,19 @@ pub type DefaultHttpClient<C> = SimpleHttpClient<C>;
 pub struct Client<P: Provider, H: HttpClient> {
     pub(crate) provider: P,
     pub(crate) http_client: H,
+    pub(crate) usage_tracker: Option<UsageTracker>,
+    pub(crate) budgets: Option<Budgets>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) moderation_guard: Option<ModerationGuard>,
+    pub(crate) middleware: MiddlewareStack,
+    pub(crate) telemetry: Telemetry,
+    pub(crate) raw_body: bool,
 }
 
 impl<P: Provider> Client<P, DefaultHttpClient<P::Config>> {
     pub fn with_provider(provider: P) -> Self {
         let config = provider.config().clone();
-        Self {
-            provider,
-            http_client: DefaultHttpClient::new(config),
-        }
+        Self::with_args(provider, DefaultHttpClient::new(config))
     }
 }
 
@@ -31,6 +47,13 @@ impl<P: Provider, H: HttpClient> Client<P, H> {
         Client {
             provider,
             http_client,
+            usage_tracker: None,
+            budgets: None,
+            rate_limiter: None,
+            moderation_guard: None,
+            middleware: MiddlewareStack::new(),
+            telemetry: Telemetry::new(),
+            raw_body: false,
         }
     }
 
@@ -41,6 +64,129 @@ impl<P: Provider, H: HttpClient> Client<P, H> {
     pub fn http_client(&self) -> &H {
         &self.http_client
     }
+
+    /// Records the usage and cost of every chat and completion call, including streamed ones, in the given tracker.
+    pub fn with_usage_tracker(mut self, usage_tracker: UsageTracker) -> Self {
+        self.usage_tracker = Some(usage_tracker);
+        self
+    }
+
+    pub fn usage_tracker(&self) -> Option<&UsageTracker> {
+        self.usage_tracker.as_ref()
+    }
+
+    /// Enforces the given budgets: calls fail with [`crate::Error::BudgetExceeded`] before sending once a hard budget is used up.
+    pub fn with_budgets(mut self, budgets: Budgets) -> Self {
+        self.budgets = Some(budgets);
+        self
+    }
+
+    pub fn budgets(&self) -> Option<&Budgets> {
+        self.budgets.as_ref()
+    }
+
+    /// Throttles calls to the limits of the given limiter. Calls over the limit wait instead of failing. The http client reports rate limit headers to it as well.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.http_client = self.http_client.with_rate_limiter(rate_limiter.clone());
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Screens the user input and the assistant output of chat and Responses API calls with the moderations endpoint.
+    pub fn with_moderation_guard(mut self, moderation_guard: ModerationGuard) -> Self {
+        self.moderation_guard = Some(moderation_guard);
+        self
+    }
+
+    pub fn moderation_guard(&self) -> Option<&ModerationGuard> {
+        self.moderation_guard.as_ref()
+    }
+
+    /// Keeps the raw body of responses in the [`ResponseMeta`](crate::http::ResponseMeta) of `*_with_meta` calls. Off by default, as the body is otherwise dropped once parsed.
+    pub fn with_raw_body(mut self, raw_body: bool) -> Self {
+        self.raw_body = raw_body;
+        self
+    }
+
+    pub fn raw_body(&self) -> bool {
+        self.raw_body
+    }
+
+    /// Adds a middleware on top of the stack, which the http client runs on every request, response and stream event.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(middleware);
+        self.http_client = self.http_client.with_middleware(self.middleware.clone());
+        self
+    }
+
+    pub fn middleware(&self) -> &MiddlewareStack {
+        &self.middleware
+    }
+
+    /// Configures the tracing spans and metrics of chat and completion calls.
+    pub fn with_telemetry(mut self, telemetry: Telemetry) -> Self {
+        self.telemetry = telemetry;
+        self
+    }
+
+    pub fn telemetry(&self) -> &Telemetry {
+        &self.telemetry
+    }
+
+    /// Starts the span of a call.
+    pub(crate) fn instrument(
+        &self,
+        operation: &'static str,
+        request: &impl Requestable,
+    ) -> Instrumentation {
+        Instrumentation::start(operation, &self.provider.name(), request, self.telemetry)
+    }
+
+    /// Whether the moderation guard screens the given stage of chat and Responses API calls.
+    pub(crate) fn moderates(&self, stage: ModerationStage) -> bool {
+        self.moderation_guard
+            .as_ref()
+            .is_some_and(|guard| guard.checks(stage))
+    }
+
+    /// Runs the moderation guard, if any, on the content of a chat call.
+    pub(crate) async fn moderate(
+        &self,
+        stage: ModerationStage,
+        input: Option<ModerationInput>,
+    ) -> Result<(), Error> {
+        if let (Some(guard), Some(input)) = (&self.moderation_guard, input) {
+            guard.screen(&self.http_client, stage, input).await?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the rate limiter, if any, before sending a request.
+    pub(crate) async fn acquire<R: Requestable>(&self, request: &R) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let tokens = request.estimate_tokens(rate_limiter.token_counter());
+            rate_limiter
+                .acquire(
+                    &self.provider.name(),
+                    request.model().unwrap_or_default(),
+                    tokens.try_into().unwrap_or(u32::MAX),
+                )
+                .await;
+        }
+    }
+
+    pub(crate) fn usage_recorder(&self, tags: &[String]) -> UsageRecorder {
+        UsageRecorder {
+            provider: self.provider.name(),
+            tags: tags.to_vec(),
+            tracker: self.usage_tracker.clone(),
+            budgets: self.budgets.clone(),
+        }
+    }
 }
 
 impl Default for Client<OpenAIProvider, DefaultHttpClient<OpenAIConfig>> {
@@ -53,19 +199,13 @@ impl Client<OpenAIProvider, DefaultHttpClient<OpenAIConfig>> {
     pub fn new() -> Self {
         let provider = OpenAIProvider::default();
         let config = provider.config().clone();
-        Self {
-            provider,
-            http_client: DefaultHttpClient::new(config),
-        }
+        Self::with_args(provider, DefaultHttpClient::new(config))
     }
 
     pub fn with_auth(base_url: impl Into<String>, api_key: Option<SecretString>) -> Self {
         let config = OpenAIConfig::new(base_url, api_key);
         let provider = OpenAIProvider::new(config.clone());
-        Self {
-            provider,
-            http_client: DefaultHttpClient::new(config),
-        }
+        Self::with_args(provider, DefaultHttpClient::new(config))
     }
 }
 
@@ -73,27 +213,47 @@ impl Client<RawProvider, DefaultHttpClient<OpenAIConfig>> {
     pub fn raw() -> Self {
         let provider = RawProvider::default();
         let config = provider.config().clone();
-        Self {
-            provider,
-            http_client: DefaultHttpClient::new(config),
-        }
+        Self::with_args(provider, DefaultHttpClient::new(config))
     }
 
     pub fn with_auth_raw(base_url: impl Into<String>, api_key: Option<SecretString>) -> Self {
         let config = OpenAIConfig::new(base_url, api_key);
         let provider = RawProvider::new(config.clone());
-        Self {
-            provider,
-            http_client: DefaultHttpClient::new(config),
-        }
+        Self::with_args(provider, DefaultHttpClient::new(config))
     }
 }
 
 impl<P: Provider, H: HttpClient> Client<P, H> {
-    pub fn completions(&self) -> Completions<P, H> {
+    pub fn completions(&self) -> Completions<'_, P, H> {
         Completions::new(self)
     }
-    pub fn chat(&self) -> Chat<P, H> {
+    pub fn chat(&self) -> Chat<'_, P, H> {
         Chat::new(self)
     }
+    /// Chat calls as a [`tower::Service`] owning a clone of the client.
+    #[cfg(feature = "tower")]
+    pub fn chat_service(&self) -> crate::chat::ChatService<P, H>
+    where
+        P: Clone,
+    {
+        crate::chat::ChatService::new(self.clone())
+    }
+    pub fn batches(&self) -> Batches<'_, P, H> {
+        Batches::new(self)
+    }
+    pub fn files(&self) -> Files<'_, P, H> {
+        Files::new(self)
+    }
+    pub fn audio(&self) -> Audio<'_, P, H> {
+        Audio::new(self)
+    }
+    pub fn images(&self) -> Images<'_, P, H> {
+        Images::new(self)
+    }
+    pub fn moderations(&self) -> Moderations<'_, P, H> {
+        Moderations::new(self)
+    }
+    pub fn responses(&self) -> Responses<'_, P, H> {
+        Responses::new(self)
+    }
 }
@@ -1,4 +1,10 @@
-use crate::{error::Error, http::HttpClient, Client, Provider};
+use tracing::Instrument;
+
+use crate::{
+    error::Error,
+    http::{HttpClient, ResponseMeta},
+    Client, Provider,
+};
 
 pub mod request;
 pub mod response;
@@ -17,9 +23,127 @@ impl<'c, P: Provider, H: HttpClient> Completions<'c, P, H> {
     }
 
     pub async fn create(&self, request: CompletionRequest) -> Result<CompletionResponse, Error> {
-        self.client
+        Ok(self.create_with_meta(request).await?.0)
+    }
+
+    /// Like [`Completions::create`], with the status, headers, request id and timing of the response, and its raw body with [`Client::with_raw_body`].
+    pub async fn create_with_meta(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<(CompletionResponse, ResponseMeta), Error> {
+        let instrumentation = self.client.instrument("text_completion", &request);
+        let result = self
+            .send(request)
+            .instrument(instrumentation.span().clone())
+            .await;
+        instrumentation.finish(result.as_ref().map(|(response, _)| response));
+        result
+    }
+
+    async fn send(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<(CompletionResponse, ResponseMeta), Error> {
+        let recorder = self.client.usage_recorder(&[]);
+        let model = request.model.clone();
+        recorder.check(Some(&model))?;
+        self.client.acquire(&request).await;
+        let (response, mut meta) = self
+            .client
             .provider
-            .completions(&self.client.http_client, request)
+            .completions_with_meta(&self.client.http_client, request)
+            .await?;
+        if let Some(usage) = &response.usage {
+            recorder.record(response.model.as_deref().unwrap_or(&model), usage);
+        }
+        if !self.client.raw_body {
+            meta.body = None;
+        }
+        Ok((response, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use bytes::Bytes;
+    use futures::Stream;
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        StatusCode,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::*;
+    use crate::{
+        http::{HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        providers::OpenAIConfig,
+        rate_limit::RateLimiter,
+        OpenAIProvider,
+    };
+
+    /// Answers every request with a completion and an `x-request-id` header.
+    #[derive(Debug, Clone)]
+    struct MockHttpClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from_static("req_123"));
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::OK,
+                headers,
+                body: Bytes::from(r#"{"id": "cmpl-1", "choices": [{"index": 0, "text": "4"}]}"#),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not mocked".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn completions_with_meta_works() {
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(OpenAIProvider::new(config), MockHttpClient);
+        let request: CompletionRequest = serde_json::from_value(
+            serde_json::json!({"model": "gpt-3.5-turbo-instruct", "prompt": "2+2="}),
+        )
+        .unwrap();
+
+        let (response, meta) = client
+            .completions()
+            .create_with_meta(request.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.id.as_deref(), Some("cmpl-1"));
+        assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+        assert!(meta.body.is_none());
+
+        let client = client.with_raw_body(true);
+        let (_, meta) = client
+            .completions()
+            .create_with_meta(request)
             .await
+            .unwrap();
+        assert!(meta.text().unwrap().contains("cmpl-1"));
     }
 }
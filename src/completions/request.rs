@@ -2,7 +2,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{Content, Stop, StreamOptions};
+use crate::{
+    context::TokenCounter,
+    request::Requestable,
+    types::{Content, Stop, StreamOptions},
+};
 
 /// https://platform.openai.com/docs/api-reference/completions/create
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -101,3 +105,34 @@ pub struct CompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
 }
+
+impl Requestable for CompletionRequest {
+    fn stream(&self) -> bool {
+        self.stream.unwrap_or(false)
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn estimate_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        let prompt = match &self.prompt {
+            Content::Text(text) => counter.count_text(text),
+            Content::Array(prompts) => prompts.iter().map(|text| counter.count_text(text)).sum(),
+        };
+        let n = self.n.unwrap_or(1).max(self.best_of.unwrap_or(1) as u8) as usize;
+        prompt + n * self.max_tokens.unwrap_or(16) as usize
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        self.max_tokens
+    }
+
+    fn prompt(&self) -> Option<String> {
+        serde_json::to_string(&self.prompt).ok()
+    }
+}
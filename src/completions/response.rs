@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::types::{CompletionChoice, CompletionUsage};
+use crate::{
+    response::Respondable,
+    types::{CompletionChoice, CompletionUsage},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CompletionResponse {
@@ -27,3 +30,28 @@ pub struct CompletionResponse {
     /// Usage statistics for the completion request.
     pub usage: Option<CompletionUsage>,
 }
+
+impl Respondable for CompletionResponse {
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.usage.clone()
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.choices.first()?.text.as_deref()
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn finish_reasons(&self) -> Vec<&str> {
+        self.choices
+            .iter()
+            .filter_map(|choice| choice.finish_reason.as_deref())
+            .collect()
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ChatMessage, ChatRequest};
+
+pub mod strategy;
+
+pub use strategy::{DropOldest, KeepLastN, Summarize, TrimStrategy};
+
+/// Estimates the number of prompt tokens of chat messages. [`ChatTokenCounter`](crate::tokenizer::ChatTokenCounter) implements it on top of any [`Tokenizer`](crate::tokenizer::Tokenizer).
+pub trait TokenCounter: Send + Sync {
+    /// The number of tokens of a single message, including the per-message overhead.
+    fn count_message(&self, message: &ChatMessage) -> usize;
+
+    /// The number of prompt tokens of a request, including tool definitions.
+    fn count_request(&self, request: &ChatRequest) -> usize {
+        let tools = request
+            .tools
+            .as_ref()
+            .map(|tools| self.count_text(&serde_json::to_string(tools).unwrap_or_default()))
+            .unwrap_or(0);
+        request
+            .messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum::<usize>()
+            + tools
+            + 3
+    }
+
+    /// The number of tokens of a plain text.
+    fn count_text(&self, text: &str) -> usize;
+}
+
+/// The token budget of a model: its context size minus the tokens reserved for the completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextWindow {
+    /// The context size of the model in tokens.
+    pub context_size: usize,
+
+    /// The tokens reserved for the completion, usually `max_completion_tokens`.
+    pub reserved: usize,
+}
+
+impl ContextWindow {
+    pub fn new(context_size: usize) -> Self {
+        Self {
+            context_size,
+            reserved: 0,
+        }
+    }
+
+    /// A window that reserves the `max_completion_tokens` (or the deprecated `max_tokens`) of the request.
+    #[allow(deprecated)]
+    pub fn for_request(context_size: usize, request: &ChatRequest) -> Self {
+        let reserved = request.max_completion_tokens.or(request.max_tokens);
+        Self::new(context_size).reserve(reserved.unwrap_or(0) as usize)
+    }
+
+    pub fn reserve(mut self, tokens: usize) -> Self {
+        self.reserved = tokens;
+        self
+    }
+
+    /// The number of tokens available for the prompt.
+    pub fn budget(&self) -> usize {
+        self.context_size.saturating_sub(self.reserved)
+    }
+}
+
+/// What a [`TrimStrategy`] removed from a request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TrimReport {
+    /// The removed messages, in their original order.
+    pub removed: Vec<ChatMessage>,
+
+    /// The summary inserted in place of the removed messages, if any.
+    pub summary: Option<String>,
+
+    /// The prompt tokens before trimming.
+    pub tokens_before: usize,
+
+    /// The prompt tokens after trimming.
+    pub tokens_after: usize,
+}
+
+impl TrimReport {
+    pub fn is_trimmed(&self) -> bool {
+        !self.removed.is_empty()
+    }
+}
+
+/// Splits messages into units that must be kept or dropped together.
+///
+/// System and developer messages are pinned and never part of a unit. An assistant message that requested tool calls forms one unit with the tool results that follow it.
+pub(crate) fn units(messages: &[ChatMessage]) -> Vec<Range<usize>> {
+    let mut units: Vec<Range<usize>> = vec![];
+    for (index, message) in messages.iter().enumerate() {
+        if message.is_system() {
+            continue;
+        }
+        match units.last_mut() {
+            Some(unit) if message.is_tool() && unit.end == index => unit.end = index + 1,
+            _ => units.push(index..index + 1),
+        }
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_keep_tool_results_with_calls() {
+        let messages = vec![
+            ChatMessage::system("You are a helpful assistant"),
+            ChatMessage::user("What's the weather like in Hanoi?"),
+            ChatMessage::assistant(""),
+            ChatMessage::tool("30 degrees", "call_1"),
+            ChatMessage::tool("sunny", "call_2"),
+            ChatMessage::assistant("It's sunny and 30 degrees."),
+        ];
+        assert_eq!(units(&messages), vec![1..2, 2..5, 5..6]);
+    }
+}
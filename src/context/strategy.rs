@@ -0,0 +1,523 @@
+use std::ops::Range;
+
+use async_trait::async_trait;
+
+use crate::{
+    error::Error,
+    http::HttpClient,
+    types::{AssistantContent, AssistantContentPart, Content, UserContent, UserContentPart},
+    ChatMessage, ChatRequest, ChatResponse, ChatResponseStream, Client, Provider,
+};
+
+use super::{units, ContextWindow, TokenCounter, TrimReport};
+
+/// Trims the `messages` of a request so that the prompt fits into a [`ContextWindow`].
+///
+/// System and developer messages are always kept, and tool results are never separated from the assistant message that requested them.
+#[async_trait]
+pub trait TrimStrategy: Send + Sync {
+    async fn trim(
+        &self,
+        request: &mut ChatRequest,
+        window: ContextWindow,
+        counter: &dyn TokenCounter,
+    ) -> Result<TrimReport, Error>;
+}
+
+/// Drops the oldest turns until the prompt fits. The last turn is always kept; if it does not fit on its own, an [`Error::ContextLengthExceeded`] is returned.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropOldest;
+
+#[async_trait]
+impl TrimStrategy for DropOldest {
+    async fn trim(
+        &self,
+        request: &mut ChatRequest,
+        window: ContextWindow,
+        counter: &dyn TokenCounter,
+    ) -> Result<TrimReport, Error> {
+        let tokens_before = counter.count_request(request);
+        let units = units(&request.messages);
+        let dropped = oldest_to_drop(request, &units, window.budget(), counter)?;
+        let removed = remove_units(request, &units[..dropped]);
+        Ok(TrimReport {
+            removed,
+            summary: None,
+            tokens_before,
+            tokens_after: counter.count_request(request),
+        })
+    }
+}
+
+/// Keeps only the last `n` messages besides system and developer messages, regardless of the token budget. Fewer messages are dropped when needed to keep tool calls and their results together.
+#[derive(Debug, Clone, Copy)]
+pub struct KeepLastN(pub usize);
+
+#[async_trait]
+impl TrimStrategy for KeepLastN {
+    async fn trim(
+        &self,
+        request: &mut ChatRequest,
+        _window: ContextWindow,
+        counter: &dyn TokenCounter,
+    ) -> Result<TrimReport, Error> {
+        let tokens_before = counter.count_request(request);
+        let units = units(&request.messages);
+        let mut remaining: usize = units.iter().map(|unit| unit.len()).sum();
+        let mut dropped = 0;
+        for unit in &units {
+            if remaining < self.0 + unit.len() {
+                break;
+            }
+            remaining -= unit.len();
+            dropped += 1;
+        }
+        let removed = remove_units(request, &units[..dropped]);
+        Ok(TrimReport {
+            removed,
+            summary: None,
+            tokens_before,
+            tokens_after: counter.count_request(request),
+        })
+    }
+}
+
+/// The start of the system message holding the summary, by which the next trim finds it.
+pub const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+pub const DEFAULT_SUMMARY_PROMPT: &str = "Summarize the following conversation between a user and an assistant. Keep every fact, decision and open question that is needed to continue the conversation. Reply with the summary only.";
+
+/// Drops the oldest turns like [`DropOldest`] and replaces them with a summary written by a secondary model call.
+///
+/// The summary is inserted as a system message right after the leading system and developer messages. A later trim folds the previous summary into the new one, so there is at most one summary message.
+#[derive(Debug, Clone)]
+pub struct Summarize<P: Provider, H: HttpClient> {
+    pub client: Client<P, H>,
+
+    /// The model used to write the summary.
+    pub model: String,
+
+    /// The instructions sent as the system message of the summary request.
+    pub prompt: String,
+
+    /// The maximum length of the summary. These tokens are reserved in the budget.
+    pub max_summary_tokens: u32,
+}
+
+impl<P: Provider, H: HttpClient> Summarize<P, H> {
+    pub fn new(client: Client<P, H>, model: impl Into<String>) -> Self {
+        Self {
+            client,
+            model: model.into(),
+            prompt: DEFAULT_SUMMARY_PROMPT.into(),
+            max_summary_tokens: 512,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    pub fn with_max_summary_tokens(mut self, max_summary_tokens: u32) -> Self {
+        self.max_summary_tokens = max_summary_tokens;
+        self
+    }
+}
+
+#[async_trait]
+impl<P, H> TrimStrategy for Summarize<P, H>
+where
+    P: Provider<
+        ChatRequest = ChatRequest,
+        ChatResponse = ChatResponse,
+        ChatResponseStream = ChatResponseStream,
+    >,
+    H: HttpClient,
+{
+    async fn trim(
+        &self,
+        request: &mut ChatRequest,
+        window: ContextWindow,
+        counter: &dyn TokenCounter,
+    ) -> Result<TrimReport, Error> {
+        let tokens_before = counter.count_request(request);
+        if tokens_before <= window.budget() {
+            return Ok(TrimReport {
+                tokens_before,
+                tokens_after: tokens_before,
+                ..Default::default()
+            });
+        }
+
+        // The previous summary is replaced by the new one, so it does not count against the budget.
+        let previous = request
+            .messages
+            .iter()
+            .position(is_summary)
+            .map(|index| (index, request.messages.remove(index)));
+        let restore = |request: &mut ChatRequest, previous: Option<(usize, ChatMessage)>| {
+            if let Some((index, summary)) = previous {
+                request.messages.insert(index, summary);
+            }
+        };
+
+        let units = units(&request.messages);
+        let budget = window
+            .budget()
+            .saturating_sub(self.max_summary_tokens as usize + 16);
+        let dropped = match oldest_to_drop(request, &units, budget, counter) {
+            Ok(dropped) => dropped,
+            Err(e) => {
+                restore(request, previous);
+                return Err(e);
+            }
+        };
+        let Some(first) = units.first().filter(|_| dropped > 0) else {
+            restore(request, previous);
+            return Ok(TrimReport {
+                tokens_before,
+                tokens_after: tokens_before,
+                ..Default::default()
+            });
+        };
+        let position = first.start;
+
+        let mut messages = unit_messages(request, &units[..dropped]);
+        if let Some((_, summary)) = &previous {
+            messages.insert(0, summary.clone());
+        }
+        let transcript = transcript(&messages);
+        let mut summary_request = ChatRequest::new(
+            &self.model,
+            vec![
+                ChatMessage::system(self.prompt.as_str()),
+                ChatMessage::user(transcript),
+            ],
+        );
+        summary_request.max_completion_tokens = Some(self.max_summary_tokens);
+        let response = match self.client.chat().create(summary_request).await {
+            Ok(response) => response,
+            Err(e) => {
+                restore(request, previous);
+                return Err(e);
+            }
+        };
+        let summary = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message)
+            .and_then(|message| message.content)
+            .unwrap_or_default();
+
+        let mut removed = remove_units(request, &units[..dropped]);
+        if let Some((_, summary)) = previous {
+            removed.insert(0, summary);
+        }
+        request.messages.insert(
+            position,
+            ChatMessage::system(format!("{SUMMARY_PREFIX}{summary}")),
+        );
+        Ok(TrimReport {
+            removed,
+            summary: Some(summary),
+            tokens_before,
+            tokens_after: counter.count_request(request),
+        })
+    }
+}
+
+/// The number of oldest units to drop so that the request fits into `budget`. The last unit is never dropped.
+fn oldest_to_drop(
+    request: &ChatRequest,
+    units: &[Range<usize>],
+    budget: usize,
+    counter: &dyn TokenCounter,
+) -> Result<usize, Error> {
+    let mut tokens = counter.count_request(request);
+    let mut dropped = 0;
+    while tokens > budget && dropped + 1 < units.len() {
+        tokens -= request.messages[units[dropped].clone()]
+            .iter()
+            .map(|message| counter.count_message(message))
+            .sum::<usize>();
+        dropped += 1;
+    }
+    if tokens > budget {
+        return Err(Error::ContextLengthExceeded(format!(
+            "the prompt needs {tokens} tokens but only {budget} are available"
+        )));
+    }
+    Ok(dropped)
+}
+
+/// Whether the message is a summary inserted by [`Summarize`].
+fn is_summary(message: &ChatMessage) -> bool {
+    matches!(message, ChatMessage::System { content: Content::Text(text), .. } if text.starts_with(SUMMARY_PREFIX))
+}
+
+/// The messages of the given units, without the pinned messages between them.
+fn unit_messages(request: &ChatRequest, units: &[Range<usize>]) -> Vec<ChatMessage> {
+    units
+        .iter()
+        .flat_map(|unit| request.messages[unit.clone()].iter().cloned())
+        .collect()
+}
+
+/// Removes the given units, which must be in order, and returns the removed messages.
+fn remove_units(request: &mut ChatRequest, units: &[Range<usize>]) -> Vec<ChatMessage> {
+    let mut removed = vec![];
+    for unit in units.iter().rev() {
+        removed.splice(0..0, request.messages.drain(unit.clone()));
+    }
+    removed
+}
+
+/// Renders messages as plain text for the summary request.
+fn transcript(messages: &[ChatMessage]) -> String {
+    messages
+        .iter()
+        .map(|message| match message {
+            ChatMessage::Developer { content, .. } | ChatMessage::System { content, .. } => {
+                format!("system: {}", content_text(content))
+            }
+            ChatMessage::User { content, .. } => format!("user: {}", user_text(content)),
+            ChatMessage::Assistant {
+                content,
+                refusal,
+                tool_calls,
+                ..
+            } => {
+                let mut text = content.as_ref().map(assistant_text).unwrap_or_default();
+                if let Some(refusal) = refusal {
+                    text.push_str(refusal);
+                }
+                for tool_call in tool_calls.iter().flatten() {
+                    text.push_str(&format!(
+                        "\n[called {}({})]",
+                        tool_call.function.name, tool_call.function.arguments
+                    ));
+                }
+                format!("assistant: {text}")
+            }
+            ChatMessage::Tool { content, .. } => format!("tool: {}", content_text(content)),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn content_text(content: &Content) -> String {
+    match content {
+        Content::Text(text) => text.clone(),
+        Content::Array(parts) => parts.join("\n"),
+    }
+}
+
+fn user_text(content: &UserContent) -> String {
+    match content {
+        UserContent::Text(text) => text.clone(),
+        UserContent::Array(parts) => parts
+            .iter()
+            .map(|part| match part {
+                UserContentPart::Text { text } => text.as_str(),
+                UserContentPart::ImageUrl { .. } => "[image]",
+                UserContentPart::Audio { .. } => "[audio]",
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn assistant_text(content: &AssistantContent) -> String {
+    match content {
+        AssistantContent::Text(text) => text.clone(),
+        AssistantContent::Array(parts) => parts
+            .iter()
+            .map(|part| match part {
+                AssistantContentPart::Text(text) => text.as_str(),
+                AssistantContentPart::Refusal { refusal } => refusal.as_str(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use bytes::Bytes;
+    use futures::Stream;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::json;
+
+    use crate::{
+        http::{HttpBody, HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        providers::OpenAIConfig,
+        rate_limit::RateLimiter,
+        tokenizer::ChatTokenCounter,
+        OpenAIProvider,
+    };
+
+    use super::*;
+
+    /// Records the transcript of each summary request and answers `summary <n>`.
+    #[derive(Debug, Clone, Default)]
+    struct MockHttpClient {
+        transcripts: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let HttpBody::Json(body) = &request.body else {
+                return Err(Error::InvalidArgument("expected a JSON body".into()));
+            };
+            let mut transcripts = self.transcripts.lock().unwrap();
+            transcripts.push(body["messages"][1]["content"].as_str().unwrap().to_string());
+            let summary = format!("summary {}", transcripts.len());
+            let body = json!({
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": summary}}]
+            });
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from(body.to_string()),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not mocked".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    fn request() -> ChatRequest {
+        ChatRequest::new(
+            "gpt-4o-mini",
+            vec![
+                ChatMessage::system("You are a helpful assistant"),
+                ChatMessage::user("a".repeat(400)),
+                ChatMessage::assistant("b".repeat(400).as_str()),
+                ChatMessage::user("Who are you?"),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_works() {
+        let mut request = request();
+        let report = DropOldest
+            .trim(
+                &mut request,
+                ContextWindow::new(100),
+                &ChatTokenCounter::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert!(report.tokens_after <= 100);
+        assert_eq!(request.messages.len(), 2);
+        assert!(request.messages[0].is_system());
+
+        let result = DropOldest
+            .trim(
+                &mut request,
+                ContextWindow::new(10),
+                &ChatTokenCounter::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::ContextLengthExceeded(_))));
+    }
+
+    #[tokio::test]
+    async fn summarize_works() {
+        let http_client = MockHttpClient::default();
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(OpenAIProvider::new(config), http_client.clone());
+        let strategy = Summarize::new(client, "gpt-4o-mini").with_max_summary_tokens(10);
+        let counter = ChatTokenCounter::default();
+
+        let mut request = request();
+        request
+            .messages
+            .insert(2, ChatMessage::system("Answer in French"));
+        let report = strategy
+            .trim(&mut request, ContextWindow::new(100), &counter)
+            .await
+            .unwrap();
+        assert_eq!(report.summary.as_deref(), Some("summary 1"));
+        assert_eq!(report.removed.len(), 2);
+        {
+            let transcripts = http_client.transcripts.lock().unwrap();
+            assert!(transcripts[0].starts_with("user: aaaa"));
+            assert!(transcripts[0].contains("assistant: bbbb"));
+            assert!(!transcripts[0].contains("Answer in French"));
+        }
+
+        request
+            .messages
+            .push(ChatMessage::assistant("c".repeat(400).as_str()));
+        request.messages.push(ChatMessage::user("And now?"));
+        let report = strategy
+            .trim(&mut request, ContextWindow::new(100), &counter)
+            .await
+            .unwrap();
+        assert_eq!(report.summary.as_deref(), Some("summary 2"));
+        let transcripts = http_client.transcripts.lock().unwrap();
+        assert!(transcripts[1].starts_with(&format!("system: {SUMMARY_PREFIX}summary 1")));
+        let summaries: Vec<_> = request.messages.iter().filter(|m| is_summary(m)).collect();
+        assert_eq!(summaries.len(), 1);
+        assert!(matches!(
+            summaries[0],
+            ChatMessage::System { content: Content::Text(text), .. } if text.ends_with("summary 2")
+        ));
+    }
+
+    #[test]
+    fn unit_messages_skips_pinned_messages() {
+        let mut request = request();
+        request
+            .messages
+            .insert(2, ChatMessage::system("Answer in French"));
+        let units = units(&request.messages);
+        let messages = unit_messages(&request, &units[..2]);
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| !message.is_system()));
+    }
+
+    #[tokio::test]
+    async fn keep_last_n_works() {
+        let mut request = request();
+        let report = KeepLastN(1)
+            .trim(
+                &mut request,
+                ContextWindow::new(0),
+                &ChatTokenCounter::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert_eq!(request.messages.len(), 2);
+    }
+}
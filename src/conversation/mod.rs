@@ -0,0 +1,479 @@
+use std::{
+    fs,
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::{ContextWindow, TokenCounter, TrimReport, TrimStrategy},
+    error::Error,
+    http::HttpClient,
+    types::Content,
+    ChatMessage, ChatRequest, ChatResponse, ChatResponseStream, Client, Printable, Provider,
+};
+
+/// A multi-turn chat session that owns the system prompt, the message history and the default request parameters.
+///
+/// Every call to [`Conversation::send`] appends the user message and the assistant reply (including tool calls) to the history, so the next request carries the whole conversation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Conversation {
+    /// The system prompt sent before the history on every request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<Content>,
+
+    /// The message history, without the system prompt.
+    pub messages: Vec<ChatMessage>,
+
+    /// The default request parameters, such as `model` and `temperature`. Its `messages` and `stream` fields are ignored.
+    pub defaults: ChatRequest,
+}
+
+impl Conversation {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            defaults: ChatRequest::from_model(model),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_system(mut self, system: impl Into<Content>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn with_defaults(mut self, defaults: ChatRequest) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.defaults.model
+    }
+
+    pub fn iter_messages(&self) -> impl Iterator<Item = &ChatMessage> {
+        self.messages.iter()
+    }
+
+    /// The last assistant message in the history, if any.
+    pub fn last_reply(&self) -> Option<&ChatMessage> {
+        self.messages.iter().rev().find(|m| m.is_assistant())
+    }
+
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+    }
+
+    pub fn push_user(&mut self, message: impl Into<String>) {
+        self.messages.push(ChatMessage::user(message));
+    }
+
+    /// Appends the result of a tool call requested by the assistant. Call [`Conversation::complete`] afterwards to let the model continue.
+    pub fn push_tool(&mut self, message: impl Into<Content>, tool_call_id: impl Into<String>) {
+        self.messages.push(ChatMessage::tool(message, tool_call_id));
+    }
+
+    /// Builds the request that would be sent for the current history: the defaults, the system prompt and all messages.
+    pub fn request(&self) -> ChatRequest {
+        let mut request = self.defaults.clone();
+        request.messages = self
+            .system
+            .iter()
+            .map(|system| ChatMessage::system(system.clone()))
+            .chain(self.messages.iter().cloned())
+            .collect();
+        request.stream = None;
+        request.stream_options = None;
+        request
+    }
+}
+
+/// Sending
+impl Conversation {
+    /// Appends a user message, sends the conversation and appends the assistant reply. The user message is removed again if the request fails.
+    pub async fn send<P, H>(
+        &mut self,
+        client: &Client<P, H>,
+        message: impl Into<String>,
+    ) -> Result<ChatResponse, Error>
+    where
+        P: Provider<
+            ChatRequest = ChatRequest,
+            ChatResponse = ChatResponse,
+            ChatResponseStream = ChatResponseStream,
+        >,
+        H: HttpClient,
+    {
+        let len = self.messages.len();
+        self.push_user(message);
+        self.complete(client).await.inspect_err(|_| {
+            self.messages.truncate(len);
+        })
+    }
+
+    /// Sends the current history as is and appends the assistant reply, e.g. after pushing tool results.
+    pub async fn complete<P, H>(&mut self, client: &Client<P, H>) -> Result<ChatResponse, Error>
+    where
+        P: Provider<
+            ChatRequest = ChatRequest,
+            ChatResponse = ChatResponse,
+            ChatResponseStream = ChatResponseStream,
+        >,
+        H: HttpClient,
+    {
+        let response = client.chat().create(self.request()).await?;
+        self.append_reply(&response);
+        Ok(response)
+    }
+
+    /// Streaming version of [`Conversation::send`]. The assistant reply is appended once the returned stream is exhausted. The user message is removed again if the stream fails or is dropped before it ends.
+    pub async fn send_stream<P, H>(
+        &mut self,
+        client: &Client<P, H>,
+        message: impl Into<String>,
+    ) -> Result<ConversationStream<'_>, Error>
+    where
+        P: Provider<
+            ChatRequest = ChatRequest,
+            ChatResponse = ChatResponse,
+            ChatResponseStream = ChatResponseStream,
+        >,
+        H: HttpClient,
+    {
+        let len = self.messages.len();
+        self.push_user(message);
+        let inner = match client.chat().create_stream(self.stream_request()).await {
+            Ok(inner) => inner,
+            Err(e) => {
+                self.messages.truncate(len);
+                return Err(e);
+            }
+        };
+        Ok(ConversationStream::new(inner, &mut self.messages).with_rollback(len))
+    }
+
+    /// Streaming version of [`Conversation::complete`].
+    pub async fn complete_stream<P, H>(
+        &mut self,
+        client: &Client<P, H>,
+    ) -> Result<ConversationStream<'_>, Error>
+    where
+        P: Provider<
+            ChatRequest = ChatRequest,
+            ChatResponse = ChatResponse,
+            ChatResponseStream = ChatResponseStream,
+        >,
+        H: HttpClient,
+    {
+        let inner = client.chat().create_stream(self.stream_request()).await?;
+        Ok(ConversationStream::new(inner, &mut self.messages))
+    }
+
+    fn stream_request(&self) -> ChatRequest {
+        let mut request = self.request().with_stream();
+        request.stream_options = self.defaults.stream_options.clone();
+        request
+    }
+
+    fn append_reply(&mut self, response: &ChatResponse) {
+        if let Some(message) = reply_message(response) {
+            self.messages.push(message);
+        }
+    }
+}
+
+fn reply_message(response: &ChatResponse) -> Option<ChatMessage> {
+    response
+        .choices
+        .first()
+        .and_then(|choice| choice.message.clone())
+        .map(Into::into)
+}
+
+/// History management
+impl Conversation {
+    /// A copy of this conversation that can continue independently.
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    /// A copy of this conversation that keeps only the first `turns` turns.
+    pub fn fork_at(&self, turns: usize) -> Self {
+        let mut fork = self.clone();
+        fork.rewind_to(turns);
+        fork
+    }
+
+    /// The number of turns, i.e. user messages, in the history.
+    pub fn turns(&self) -> usize {
+        self.messages.iter().filter(|m| m.is_user()).count()
+    }
+
+    /// Removes the last turn: the last user message and everything after it. Returns the removed messages.
+    pub fn undo(&mut self) -> Option<Vec<ChatMessage>> {
+        let start = self.messages.iter().rposition(|m| m.is_user())?;
+        Some(self.messages.split_off(start))
+    }
+
+    /// Removes the last `turns` turns. Returns the removed messages.
+    pub fn rewind(&mut self, turns: usize) -> Vec<ChatMessage> {
+        self.rewind_to(self.turns().saturating_sub(turns))
+    }
+
+    /// Keeps only the first `turns` turns. Returns the removed messages.
+    pub fn rewind_to(&mut self, turns: usize) -> Vec<ChatMessage> {
+        let start = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.is_user())
+            .nth(turns)
+            .map(|(index, _)| index);
+        match start {
+            Some(start) => self.messages.split_off(start),
+            None => vec![],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Trims the history with the given strategy so that the next request fits into the context window. The system prompt is always kept.
+    pub async fn trim(
+        &mut self,
+        strategy: &dyn TrimStrategy,
+        window: ContextWindow,
+        counter: &dyn TokenCounter,
+    ) -> Result<TrimReport, Error> {
+        let mut request = self.request();
+        let report = strategy.trim(&mut request, window, counter).await?;
+        let skip = usize::from(self.system.is_some());
+        self.messages = request.messages.into_iter().skip(skip).collect();
+        Ok(report)
+    }
+}
+
+/// Persistence
+impl Conversation {
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        fs::write(path, self.to_string_pretty()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+impl Printable for Conversation {
+    fn to_string_pretty(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// The stream returned by [`Conversation::send_stream`]. It yields the chunks unchanged and appends the accumulated assistant message to the conversation when the stream ends.
+///
+/// If the stream fails or is dropped before it ends, no assistant message is appended and the user message sent by [`Conversation::send_stream`] is removed, so the history stays well-formed.
+pub struct ConversationStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<ChatResponseStream, Error>> + Send>>,
+    messages: &'a mut Vec<ChatMessage>,
+    response: ChatResponse,
+    /// The history length to restore if the stream does not complete.
+    rollback: Option<usize>,
+    done: bool,
+}
+
+impl<'a> ConversationStream<'a> {
+    fn new(
+        inner: Pin<Box<dyn Stream<Item = Result<ChatResponseStream, Error>> + Send>>,
+        messages: &'a mut Vec<ChatMessage>,
+    ) -> Self {
+        Self {
+            inner,
+            messages,
+            response: ChatResponse::from_stream([]),
+            rollback: None,
+            done: false,
+        }
+    }
+
+    fn with_rollback(mut self, len: usize) -> Self {
+        self.rollback = Some(len);
+        self
+    }
+
+    fn rollback(&mut self) {
+        if let Some(len) = self.rollback.take() {
+            self.messages.truncate(len);
+        }
+    }
+
+    /// The response accumulated from the chunks received so far.
+    pub fn response(&self) -> &ChatResponse {
+        &self.response
+    }
+}
+
+impl Stream for ConversationStream<'_> {
+    type Item = Result<ChatResponseStream, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let this = &mut *self;
+        match this.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                this.response.merge_stream(chunk.clone());
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                this.done = true;
+                this.rollback();
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => {
+                this.done = true;
+                this.rollback = None;
+                if let Some(message) = reply_message(&this.response) {
+                    this.messages.push(message);
+                }
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for ConversationStream<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.rollback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Conversation {
+        let mut conversation =
+            Conversation::new("gpt-4o-mini").with_system("You are a helpful assistant");
+        conversation.push_user("Hi");
+        conversation.push(ChatMessage::assistant("Hello!"));
+        conversation.push_user("1 + 1 =");
+        conversation.push(ChatMessage::assistant("2"));
+        conversation
+    }
+
+    #[test]
+    fn conversation_request_works() {
+        let request = conversation().request();
+        assert_eq!(request.model, "gpt-4o-mini");
+        assert_eq!(request.messages.len(), 5);
+        assert!(request.messages[0].is_system());
+    }
+
+    #[test]
+    fn conversation_undo_rewind_works() {
+        let mut conversation = conversation();
+        assert_eq!(conversation.turns(), 2);
+        assert_eq!(conversation.fork_at(1).messages.len(), 2);
+
+        let removed = conversation.undo().unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(conversation.turns(), 1);
+
+        assert_eq!(conversation.rewind(5).len(), 2);
+        assert!(conversation.messages.is_empty());
+        assert!(conversation.undo().is_none());
+    }
+
+    #[test]
+    fn conversation_serde_works() {
+        let conversation = conversation();
+        let json = conversation.to_json().unwrap();
+        assert_eq!(Conversation::from_json(&json).unwrap(), conversation);
+    }
+
+    fn chunk(content: &str) -> Result<ChatResponseStream, Error> {
+        let chunk = serde_json::json!({
+            "id": "1",
+            "choices": [{"index": 0, "delta": {"role": "assistant", "content": content}}]
+        });
+        Ok(serde_json::from_value(chunk).unwrap())
+    }
+
+    #[tokio::test]
+    async fn conversation_stream_rollback_works() {
+        let mut conversation = conversation();
+        conversation.push_user("2 + 2 =");
+        let len = conversation.messages.len();
+
+        let chunks = vec![chunk("4"), chunk(".")];
+        let inner = Box::pin(futures::stream::iter(chunks));
+        let stream =
+            ConversationStream::new(inner, &mut conversation.messages).with_rollback(len - 1);
+        assert_eq!(stream.collect::<Vec<_>>().await.len(), 2);
+        assert_eq!(conversation.messages.len(), len + 1);
+        assert_eq!(
+            conversation.last_reply().unwrap(),
+            &ChatMessage::assistant("4.")
+        );
+
+        conversation.push_user("3 + 3 =");
+        let len = conversation.messages.len();
+        let chunks = vec![chunk("6"), Err(Error::Stream("connection reset".into()))];
+        let inner = Box::pin(futures::stream::iter(chunks));
+        let stream =
+            ConversationStream::new(inner, &mut conversation.messages).with_rollback(len - 1);
+        let results = stream.collect::<Vec<_>>().await;
+        assert!(results[1].is_err());
+        assert_eq!(conversation.messages.len(), len - 1);
+
+        conversation.push_user("3 + 3 =");
+        let inner = Box::pin(futures::stream::iter(vec![chunk("6"), chunk("")]));
+        let mut stream =
+            ConversationStream::new(inner, &mut conversation.messages).with_rollback(len - 1);
+        assert!(stream.next().await.is_some());
+        drop(stream);
+        assert_eq!(conversation.messages.len(), len - 1);
+    }
+
+    #[test]
+    fn conversation_stream_reply_works() {
+        let chunks: Vec<ChatResponseStream> = [
+            r#"{"id":"1","choices":[{"index":0,"delta":{"role":"assistant","tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":""}}]}}]}"#,
+            r#"{"id":"1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"location\":"}}]}}]}"#,
+            r#"{"id":"1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"Hanoi\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+        ]
+        .iter()
+        .map(|chunk| serde_json::from_str(chunk).unwrap())
+        .collect();
+
+        let response = ChatResponse::from_stream(chunks);
+        let message: ChatMessage = response.choices[0].message.clone().unwrap().into();
+        match message {
+            ChatMessage::Assistant { tool_calls, .. } => {
+                let tool_calls = tool_calls.unwrap();
+                assert_eq!(tool_calls.len(), 1);
+                assert_eq!(tool_calls[0].id, "call_1");
+                assert_eq!(tool_calls[0].function.arguments, r#"{"location":"Hanoi"}"#);
+            }
+            _ => panic!("expected an assistant message"),
+        }
+    }
+}
@@ -12,13 +12,119 @@ pub enum Error {
     #[error("invalid config: {0}")]
     InvalidConfig(String),
 
+    // -- Context
+    #[error("context length exceeded: {0}")]
+    ContextLengthExceeded(String),
+
+    // -- Usage
+    #[error("budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    // -- Moderation
+    #[error("content rejected by moderation: {0}")]
+    Moderation(String),
+
+    #[error("model refused: {0}")]
+    Refusal(String),
+
     // -- Execution
     #[error("http client error: {0}")]
     HttpClient(String),
 
+    /// The connection failed, timed out or was dropped while reading the body.
+    #[error("transport error: {0}")]
+    Transport(String),
+
+    #[error("api error: {0}")]
+    Api(Box<ApiError>),
+
     #[error("stream error: {0}")]
     Stream(String),
 
+    #[error("request cancelled")]
+    Cancelled,
+
+    #[error("batch request failed: {0}")]
+    Batch(String),
+
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    /// Whether sending the same request again may succeed: transport errors and API errors with a retryable status.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Api(error) => error.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+/// An error reported by the provider, either with an unsuccessful status code or in an `error` object of the body or of a streamed event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    /// The status code of the response. `None` for errors sent in a stream after it opened.
+    pub status: Option<u16>,
+    pub message: String,
+    pub r#type: Option<String>,
+    /// The error code, e.g. `rate_limit_exceeded`. Some providers send a number, e.g. OpenRouter's `502`.
+    pub code: Option<String>,
+    pub param: Option<String>,
+    /// The raw body or event data.
+    pub body: String,
+}
+
+impl ApiError {
+    /// Builds the error from a response body, e.g. `{"error": {"message": "...", "type": "...", "code": "..."}}`. A body that is not JSON becomes the message.
+    pub fn from_body(status: Option<u16>, body: &str) -> Self {
+        let value: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+        let error = value.get("error").unwrap_or(&value);
+        let field = |name: &str| match error.get(name) {
+            Some(serde_json::Value::String(s)) => Some(s.clone()),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let message = error
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| field("message"))
+            .unwrap_or_else(|| body.trim().to_string());
+        Self {
+            status,
+            message,
+            r#type: field("type"),
+            code: field("code"),
+            param: field("param"),
+            body: body.to_string(),
+        }
+    }
+
+    /// Whether the request may succeed if sent again: timeouts, conflicts, rate limits and server errors.
+    pub fn is_retryable(&self) -> bool {
+        let status = self
+            .status
+            .or_else(|| self.code.as_deref().and_then(|code| code.parse().ok()));
+        matches!(status, Some(408 | 409 | 429 | 500..=599))
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(status) = self.status {
+            write!(f, ", status = {status}")?;
+        }
+        if let Some(r#type) = &self.r#type {
+            write!(f, ", type = {type}")?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, ", code = {code}")?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    error::Error,
+    http::{path_segment, HttpClient},
+    types::{DeletedFile, FileList, FileObject, FilePurpose},
+    Client, Provider,
+};
+
+/// The content of a multipart file field, read from disk or from memory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileInput {
+    Path(PathBuf),
+    Bytes { filename: String, content: Bytes },
+}
+
+impl FileInput {
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    pub fn from_bytes(filename: impl Into<String>, content: impl Into<Bytes>) -> Self {
+        Self::Bytes {
+            filename: filename.into(),
+            content: content.into(),
+        }
+    }
+
+    /// The multipart part. Files on disk are streamed rather than loaded into memory.
+    pub async fn into_part(self) -> Result<Part, Error> {
+        match self {
+            FileInput::Path(path) => {
+                let file = tokio::fs::File::open(&path).await?;
+                let length = file.metadata().await?.len();
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "file".into());
+                Ok(
+                    Part::stream_with_length(Body::wrap_stream(ReaderStream::new(file)), length)
+                        .file_name(filename),
+                )
+            }
+            FileInput::Bytes { filename, content } => {
+                let length = content.len() as u64;
+                Ok(Part::stream_with_length(content, length).file_name(filename))
+            }
+        }
+    }
+}
+
+impl From<PathBuf> for FileInput {
+    fn from(value: PathBuf) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<&Path> for FileInput {
+    fn from(value: &Path) -> Self {
+        Self::Path(value.to_path_buf())
+    }
+}
+
+impl From<&str> for FileInput {
+    fn from(value: &str) -> Self {
+        Self::Path(value.into())
+    }
+}
+
+/// The Files API: files used by batches, fine-tuning and `file` content parts.
+#[derive(Debug, Clone)]
+pub struct Files<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Files<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self { client }
+    }
+
+    /// Uploads a file from disk. The content is streamed, so large files are not loaded into memory.
+    pub async fn upload(
+        &self,
+        path: impl AsRef<Path>,
+        purpose: FilePurpose,
+    ) -> Result<FileObject, Error> {
+        let part = FileInput::from_path(path.as_ref()).into_part().await?;
+        self.upload_part(part, purpose).await
+    }
+
+    /// Uploads a file from memory.
+    pub async fn upload_bytes(
+        &self,
+        filename: impl Into<String>,
+        content: impl Into<Bytes>,
+        purpose: FilePurpose,
+    ) -> Result<FileObject, Error> {
+        let part = FileInput::from_bytes(filename, content).into_part().await?;
+        self.upload_part(part, purpose).await
+    }
+
+    async fn upload_part(&self, part: Part, purpose: FilePurpose) -> Result<FileObject, Error> {
+        let form = Form::new()
+            .text("purpose", purpose.as_str().to_string())
+            .part("file", part);
+        self.client.http_client.post_multipart("/files", form).await
+    }
+
+    /// Lists files, optionally only those with the given purpose. `after` is the ID of the last file of the previous page.
+    pub async fn list(
+        &self,
+        purpose: Option<FilePurpose>,
+        after: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<FileList, Error> {
+        let limit = limit.map(|limit| limit.to_string());
+        let mut query = vec![];
+        if let Some(purpose) = &purpose {
+            query.push(("purpose", purpose.as_str()));
+        }
+        if let Some(after) = after {
+            query.push(("after", after));
+        }
+        if let Some(limit) = &limit {
+            query.push(("limit", limit.as_str()));
+        }
+        self.client.http_client.get("/files", &query).await
+    }
+
+    pub async fn retrieve(&self, file_id: &str) -> Result<FileObject, Error> {
+        self.client
+            .http_client
+            .get(&format!("/files/{}", path_segment(file_id)), &[])
+            .await
+    }
+
+    /// Downloads the content of a file.
+    pub async fn content(&self, file_id: &str) -> Result<Bytes, Error> {
+        self.client
+            .http_client
+            .get_bytes(&format!("/files/{}/content", path_segment(file_id)))
+            .await
+    }
+
+    /// Downloads the content of a file to disk.
+    pub async fn download(&self, file_id: &str, path: impl AsRef<Path>) -> Result<(), Error> {
+        let content = self.content(file_id).await?;
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, file_id: &str) -> Result<DeletedFile, Error> {
+        self.client
+            .http_client
+            .delete(&format!("/files/{}", path_segment(file_id)))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::Stream;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        http::{HttpBody, HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        providers::OpenAIConfig,
+        rate_limit::RateLimiter,
+        OpenAIProvider,
+    };
+
+    /// Records each request as `<method> <path>[?<query>][ multipart]` and answers like the Files API.
+    #[derive(Debug, Clone, Default)]
+    struct MockHttpClient {
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let mut line = format!("{} {}", request.method, request.path);
+            if !request.query.is_empty() {
+                let query: Vec<String> = request
+                    .query
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect();
+                line.push_str(&format!("?{}", query.join("&")));
+            }
+            if matches!(request.body, HttpBody::Multipart(_)) {
+                line.push_str(" multipart");
+            }
+            self.requests.lock().unwrap().push(line);
+
+            let file = json!({"id": "file-1", "object": "file", "bytes": 5, "filename": "input.jsonl", "purpose": "batch"});
+            let body = match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.ends_with("/content") => Bytes::from_static(b"hello"),
+                ("GET", "/files") => json!({"object": "list", "data": [file], "has_more": false})
+                    .to_string()
+                    .into(),
+                ("DELETE", _) => json!({"id": "file-1", "object": "file", "deleted": true})
+                    .to_string()
+                    .into(),
+                _ => file.to_string().into(),
+            };
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body,
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not mocked".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn files_work() {
+        let http_client = MockHttpClient::default();
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(OpenAIProvider::new(config), http_client.clone());
+        let files = client.files();
+
+        let file = files
+            .upload_bytes("input.jsonl", "hello", FilePurpose::Batch)
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file-1");
+        let list = files
+            .list(Some(FilePurpose::Batch), Some("file-0"), Some(10))
+            .await
+            .unwrap();
+        assert_eq!(list.data, vec![file]);
+        assert_eq!(files.retrieve("file-1").await.unwrap().id, "file-1");
+        assert_eq!(files.content("file-1").await.unwrap(), "hello");
+
+        let path = std::env::temp_dir().join("async-llm-files-work.jsonl");
+        files.download("file-1", &path).await.unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"hello");
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert!(files.delete("../batches/file 1").await.unwrap().deleted);
+        assert_eq!(
+            *http_client.requests.lock().unwrap(),
+            vec![
+                "POST /files multipart",
+                "GET /files?purpose=batch&after=file-0&limit=10",
+                "GET /files/file-1",
+                "GET /files/file-1/content",
+                "GET /files/file-1/content",
+                "DELETE /files/..%2Fbatches%2Ffile%201",
+            ]
+        );
+    }
+}
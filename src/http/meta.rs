@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::{header::HeaderMap, StatusCode};
+
+/// The metadata of a response, to log calls and correlate them with provider support tickets.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMeta {
+    pub url: String,
+    pub status: StatusCode,
+    /// All response headers, e.g. `openai-processing-ms` or the `x-ratelimit-*` headers.
+    pub headers: HeaderMap,
+    /// The `x-request-id` (or `request-id`) header.
+    pub request_id: Option<String>,
+    /// The time from sending the request to receiving the response headers.
+    pub time_to_first_byte: Option<Duration>,
+    /// The time from sending the request to reading the whole body. `None` for streams.
+    pub latency: Option<Duration>,
+    /// The raw body, kept when enabled with [`Client::with_raw_body`](crate::Client::with_raw_body). `None` for streams.
+    pub body: Option<Bytes>,
+}
+
+impl ResponseMeta {
+    pub fn new(url: impl Into<String>, status: StatusCode, headers: HeaderMap) -> Self {
+        let request_id = ["x-request-id", "request-id"]
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok())
+            .map(str::to_string);
+        Self {
+            url: url.into(),
+            status,
+            headers,
+            request_id,
+            time_to_first_byte: None,
+            latency: None,
+            body: None,
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// The time the provider reports it spent on the request, from the `openai-processing-ms` header.
+    pub fn processing_time(&self) -> Option<Duration> {
+        let ms: f64 = self.header("openai-processing-ms")?.parse().ok()?;
+        Some(Duration::from_secs_f64(ms / 1000.0))
+    }
+
+    /// The raw body as text.
+    pub fn text(&self) -> Option<String> {
+        self.body
+            .as_ref()
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+    use crate::http::HttpResponse;
+
+    #[test]
+    fn response_meta_works() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("req_123"));
+        headers.insert("openai-processing-ms", HeaderValue::from_static("250"));
+        let response = HttpResponse {
+            url: "https://api.openai.com/v1/chat/completions".into(),
+            status: StatusCode::OK,
+            headers,
+            body: Bytes::from_static(b"{}"),
+            time_to_first_byte: Some(Duration::from_millis(300)),
+            latency: Some(Duration::from_millis(400)),
+        };
+        let meta = response.meta();
+        assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+        assert_eq!(meta.header("openai-processing-ms"), Some("250"));
+        assert_eq!(meta.processing_time(), Some(Duration::from_millis(250)));
+        assert_eq!(meta.latency, Some(Duration::from_millis(400)));
+        assert_eq!(meta.text().as_deref(), Some("{}"));
+    }
+}
@@ -1,23 +1,131 @@
+use bytes::Bytes;
 use futures::Stream;
+use reqwest::multipart::Form;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{fmt::Debug, pin::Pin};
 
-use crate::error::Error;
+use crate::{error::Error, middleware::MiddlewareStack, rate_limit::RateLimiter};
 
+pub mod meta;
+pub mod request;
 pub mod simple;
 pub mod stream;
+#[cfg(feature = "tower")]
+pub mod tower;
+pub use meta::ResponseMeta;
+pub use request::{path_segment, HttpBody, HttpRequest, HttpResponse};
 pub use simple::SimpleHttpClient;
+#[cfg(feature = "tower")]
+pub use tower::TowerHttpClient;
 
 #[async_trait::async_trait]
-pub trait HttpClient: Debug + Clone + Send + Sync {
+pub trait HttpClient: Debug + Clone + Send + Sync + 'static {
+    /// Sends a request and reads the whole response, whatever its status code.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error>;
+
+    async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+        request: I,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error>;
+
+    /// Reports the `x-ratelimit-*` headers of responses to the limiter. Required, so that a client cannot silently ignore [`crate::Client::with_rate_limiter`]; a client that cannot report them returns `self`.
+    fn with_rate_limiter(self, rate_limiter: RateLimiter) -> Self;
+
+    /// Runs the middlewares on requests, responses and stream events. Required, so that a client cannot silently ignore [`crate::Client::with_middleware`].
+    fn with_middleware(self, middleware: MiddlewareStack) -> Self;
+
+    /// Like [`HttpClient::post_stream`], with the metadata of the response. The default implementation returns empty metadata.
+    async fn post_stream_with_meta<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+        request: I,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        Ok((
+            self.post_stream(path, request).await?,
+            ResponseMeta::default(),
+        ))
+    }
+
+    /// Sends a request and streams the raw body of a successful response while it downloads. The default implementation reads the whole body first.
+    async fn send_stream(
+        &self,
+        request: HttpRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>, Error> {
+        let body = self.request_bytes(request).await?;
+        Ok(Box::pin(futures::stream::once(async move { Ok(body) })))
+    }
+
+    /// Sends a request and parses the JSON body of a successful response.
+    async fn request_json<O: DeserializeOwned>(&self, request: HttpRequest) -> Result<O, Error> {
+        self.send(request).await?.error_for_status()?.json()
+    }
+
+    /// Sends a request and returns the raw body of a successful response, e.g. audio or file content.
+    async fn request_bytes(&self, request: HttpRequest) -> Result<Bytes, Error> {
+        Ok(self.send(request).await?.error_for_status()?.body)
+    }
+
+    /// Sends a request and parses the JSON body of a successful response, with the metadata of the response.
+    async fn request_with_meta<O: DeserializeOwned>(
+        &self,
+        request: HttpRequest,
+    ) -> Result<(O, ResponseMeta), Error> {
+        let response = self.send(request).await?.error_for_status()?;
+        Ok((response.json()?, response.meta()))
+    }
+
     async fn post<I: Serialize + Send, O: DeserializeOwned>(
         &self,
         path: &str,
         request: I,
-    ) -> Result<O, Error>;
-    async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+    ) -> Result<O, Error> {
+        self.request_json(HttpRequest::post(path).with_json(request)?)
+            .await
+    }
+
+    async fn post_with_meta<I: Serialize + Send, O: DeserializeOwned>(
         &self,
         path: &str,
         request: I,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error>;
+    ) -> Result<(O, ResponseMeta), Error> {
+        self.request_with_meta(HttpRequest::post(path).with_json(request)?)
+            .await
+    }
+
+    async fn get<O: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<O, Error> {
+        let request = query
+            .iter()
+            .fold(HttpRequest::get(path), |request, (key, value)| {
+                request.with_query(*key, *value)
+            });
+        self.request_json(request).await
+    }
+
+    async fn get_bytes(&self, path: &str) -> Result<Bytes, Error> {
+        self.request_bytes(HttpRequest::get(path)).await
+    }
+
+    async fn post_multipart<O: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: Form,
+    ) -> Result<O, Error> {
+        self.request_json(HttpRequest::post(path).with_multipart(form))
+            .await
+    }
+
+    async fn delete<O: DeserializeOwned>(&self, path: &str) -> Result<O, Error> {
+        self.request_json(HttpRequest::delete(path)).await
+    }
 }
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    multipart::Form,
+    Method, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{ApiError, Error};
+
+use super::ResponseMeta;
+
+#[derive(Debug, Default)]
+pub enum HttpBody {
+    #[default]
+    Empty,
+    Json(serde_json::Value),
+    Multipart(Form),
+}
+
+/// A request to a path relative to the provider's base url, or to an absolute url, e.g. of a generated image.
+#[derive(Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Headers sent in addition to the headers of the provider's config, replacing those with the same name.
+    pub headers: HeaderMap,
+    pub body: HttpBody,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            query: vec![],
+            headers: HeaderMap::new(),
+            body: HttpBody::Empty,
+        }
+    }
+
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    pub fn delete(path: impl Into<String>) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    /// Whether `path` is an absolute url, which is requested without the headers and query of the provider's config so that its API key is not sent to another host.
+    pub fn is_absolute(&self) -> bool {
+        self.path.starts_with("https://") || self.path.starts_with("http://")
+    }
+
+    pub fn with_query(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// The JSON body, if any.
+    pub fn json_mut(&mut self) -> Option<&mut serde_json::Value> {
+        match &mut self.body {
+            HttpBody::Json(body) => Some(body),
+            _ => None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, Error> {
+        let name: HeaderName = name
+            .parse()
+            .map_err(|e| Error::InvalidArgument(format!("Invalid header name. Error = {e:?}")))?;
+        let value: HeaderValue = value
+            .parse()
+            .map_err(|e| Error::InvalidArgument(format!("Invalid header value. Error = {e:?}")))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    pub fn with_json<I: Serialize>(mut self, body: I) -> Result<Self, Error> {
+        self.body = HttpBody::Json(serde_json::to_value(body)?);
+        Ok(self)
+    }
+
+    pub fn with_multipart(mut self, form: Form) -> Self {
+        self.body = HttpBody::Multipart(form);
+        self
+    }
+
+    /// The `model` of a JSON body.
+    pub fn model(&self) -> Option<&str> {
+        match &self.body {
+            HttpBody::Json(body) => body.get("model").and_then(|model| model.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Percent-encodes a value, e.g. a file id, so it stays a single segment of a request path.
+pub fn path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// A response with its whole body read.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// The time from sending the request to receiving the response headers.
+    pub time_to_first_byte: Option<Duration>,
+    /// The time from sending the request to reading the whole body.
+    pub latency: Option<Duration>,
+}
+
+impl HttpResponse {
+    /// Fails with the response body if the status code is not a success.
+    pub fn error_for_status(self) -> Result<Self, Error> {
+        match self.status.is_success() {
+            true => Ok(self),
+            false => Err(self.error()),
+        }
+    }
+
+    /// The error of an unsuccessful response, with its status code and body.
+    pub fn error(&self) -> Error {
+        Error::Api(Box::new(ApiError::from_body(
+            Some(self.status.as_u16()),
+            &self.text(),
+        )))
+    }
+
+    pub fn json<O: DeserializeOwned>(&self) -> Result<O, Error> {
+        serde_json::from_slice(&self.body).map_err(|e| {
+            Error::HttpClient(format!(
+                "Failed to read JSON from HTTP request. Error = {}, url = {}",
+                e, self.url
+            ))
+        })
+    }
+
+    /// Replaces the body with the given JSON.
+    pub fn set_json<I: Serialize>(&mut self, body: &I) -> Result<(), Error> {
+        self.body = serde_json::to_vec(body)?.into();
+        Ok(())
+    }
+
+    /// The status, headers, timing and raw body of the response.
+    pub fn meta(&self) -> ResponseMeta {
+        ResponseMeta {
+            time_to_first_byte: self.time_to_first_byte,
+            latency: self.latency,
+            body: Some(self.body.clone()),
+            ..ResponseMeta::new(self.url.clone(), self.status, self.headers.clone())
+        }
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
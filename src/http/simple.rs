@@ -1,74 +1,56 @@
-use std::pin::Pin;
+use std::{pin::Pin, time::Instant};
 
-use futures::Stream;
-use reqwest_eventsource::RequestBuilderExt;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE},
+    Response,
+};
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{error::Error, providers::Config};
+use crate::{
+    error::Error, middleware::MiddlewareStack, providers::Config, rate_limit::RateLimiter,
+};
 
-use super::{stream::stream, HttpClient};
+use super::{stream::stream, HttpBody, HttpClient, HttpRequest, HttpResponse, ResponseMeta};
 
 #[derive(Debug, Clone)]
 pub struct SimpleHttpClient<C: Config> {
     pub(crate) client: reqwest::Client,
     pub(crate) config: C,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) middleware: MiddlewareStack,
 }
 
 #[async_trait::async_trait]
 impl<C: Config> HttpClient for SimpleHttpClient<C> {
-    async fn post<I: Serialize + Send, O: DeserializeOwned>(
-        &self,
-        path: &str,
-        request: I,
-    ) -> Result<O, Error> {
-        let url = self.config.url(path);
-        let headers = self.config.headers()?;
-        let query = self.config.query();
-        let resp = self
-            .client
-            .post(&url)
-            .headers(headers)
-            .query(&query)
-            .json(&request)
-            .send()
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let start = Instant::now();
+        let (resp, url) = self.execute(request).await?;
+        let time_to_first_byte = start.elapsed();
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = resp
+            .bytes()
             .await
-            .map_err(|e| {
-                Error::HttpClient(format!(
-                    "Failed to send HTTP request. Error = {}, url = {url:?}",
-                    e
-                ))
-            })?;
-        let status_code = resp.status();
-        if status_code.is_success() {
-            let value: serde_json::Value = resp.json().await.map_err(|e| {
-                Error::HttpClient(format!(
-                    "Failed to read JSON from HTTP request. Error = {}, url = {url}",
-                    e
-                ))
-            })?;
-            if value.get("choices").is_some() {
-                return Ok(serde_json::from_value(value.clone())?);
-            }
-            if let Some(error) = value.get("error") {
-                return Err(Error::HttpClient(format!(
-                    "Failed to process HTTP request. Error = {error:?}, url = {url}",
-                )));
-            } else {
-                return Err(Error::HttpClient(format!(
-                    "Failed to process HTTP request. url = {url:?}",
-                )));
-            }
-        } else {
-            let body = resp.text().await.map_err(|e| {
-                Error::HttpClient(format!(
-                    "Failed to read text from HTTP request. Error = {}, url = {url}",
-                    e
-                ))
-            })?;
-            Err(Error::HttpClient(format!(
-            "Failed to process HTTP request. Status Code = {status_code:?}, url = {url} - body = {body}",
-        )))
-        }
+            .map_err(|e| reqwest_error(e, "Failed to read bytes from HTTP request", &url))?;
+        let mut response = HttpResponse {
+            url,
+            status,
+            headers,
+            body,
+            time_to_first_byte: Some(time_to_first_byte),
+            latency: Some(start.elapsed()),
+        };
+        self.middleware.on_response(&mut response)?;
+        Ok(response)
+    }
+
+    async fn send_stream(
+        &self,
+        request: HttpRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>, Error> {
+        Ok(self.open_stream(request).await?.0)
     }
 
     async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
@@ -76,20 +58,40 @@ impl<C: Config> HttpClient for SimpleHttpClient<C> {
         path: &str,
         request: I,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
-        let url = self.config.url(path);
-        let headers = self.config.headers()?;
-        let query = self.config.query();
-        let event_source = self
-            .client
-            .post(url)
-            .headers(headers)
-            .query(&query)
-            .json(&request)
-            .eventsource()
-            .map_err(|e| {
-                Error::HttpClient(format!("Failed to send HTTP request. Error = {}", e))
-            })?;
-        stream(event_source, self.config.stream_done_message()).await
+        Ok(self.post_stream_with_meta(path, request).await?.0)
+    }
+
+    async fn post_stream_with_meta<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+        request: I,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        let request = HttpRequest::post(path)
+            .with_json(request)?
+            .with_header("Accept", "text/event-stream")?;
+        let (body, meta) = self.open_stream(request).await?;
+        let stream = stream(
+            body,
+            self.config.stream_done_message(),
+            self.middleware.clone(),
+        );
+        Ok((stream, meta))
+    }
+
+    fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    fn with_middleware(mut self, middleware: MiddlewareStack) -> Self {
+        self.middleware = middleware;
+        self
     }
 }
 
@@ -98,6 +100,97 @@ impl<C: Config> SimpleHttpClient<C> {
         Self {
             client: reqwest::Client::new(),
             config,
+            rate_limiter: None,
+            middleware: MiddlewareStack::new(),
+        }
+    }
+
+    /// Sends the request and streams the body of a successful response, with the metadata of the response.
+    async fn open_stream(
+        &self,
+        request: HttpRequest,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        let start = Instant::now();
+        let (resp, url) = self.execute(request).await?;
+        let time_to_first_byte = start.elapsed();
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        if !status.is_success() {
+            let body = resp.bytes().await.unwrap_or_default();
+            return Err(HttpResponse {
+                url,
+                status,
+                headers,
+                body,
+                time_to_first_byte: Some(time_to_first_byte),
+                latency: Some(start.elapsed()),
+            }
+            .error());
         }
+        let meta = ResponseMeta {
+            time_to_first_byte: Some(time_to_first_byte),
+            ..ResponseMeta::new(url.clone(), status, headers)
+        };
+        let body = resp.bytes_stream().map(move |chunk| {
+            chunk.map_err(|e| reqwest_error(e, "Failed to read bytes from HTTP request", &url))
+        });
+        Ok((Box::pin(body), meta))
+    }
+
+    /// Sends the request and returns the response, whose body is not read yet, with the request url.
+    async fn execute(&self, mut request: HttpRequest) -> Result<(Response, String), Error> {
+        self.middleware.on_request(&mut request)?;
+        let (url, mut headers, config_query) = match request.is_absolute() {
+            true => (request.path.clone(), HeaderMap::new(), vec![]),
+            false => (
+                self.config.url(&request.path),
+                self.config.headers()?,
+                self.config.query(),
+            ),
+        };
+        if matches!(request.body, HttpBody::Multipart(_)) {
+            // reqwest sets the multipart content type with its boundary.
+            headers.remove(CONTENT_TYPE);
+        }
+        let model = request.model().map(str::to_string);
+        headers.extend(request.headers);
+        let builder = self
+            .client
+            .request(request.method, &url)
+            .headers(headers)
+            .query(&config_query)
+            .query(&request.query);
+        let builder = match request.body {
+            HttpBody::Empty => builder,
+            HttpBody::Json(body) => builder.json(&body),
+            HttpBody::Multipart(form) => builder.multipart(form),
+        };
+        let resp = builder
+            .send()
+            .await
+            .map_err(|e| reqwest_error(e, "Failed to send HTTP request", &url))?;
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.observe(
+                &self.config.provider_name(),
+                model.as_deref().unwrap_or_default(),
+                resp.headers(),
+            );
+        }
+        Ok((resp, url))
+    }
+}
+
+/// Connection failures, timeouts and bodies cut off are transport errors, which may succeed when retried.
+fn reqwest_error(e: reqwest::Error, message: &str, url: &str) -> Error {
+    let message = format!("{message}. Error = {e}, url = {url}");
+    match e.is_connect() || e.is_timeout() || e.is_body() {
+        true => Error::Transport(message),
+        false => Error::HttpClient(message),
     }
 }
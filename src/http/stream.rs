@@ -1,45 +1,194 @@
-use std::pin::Pin;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
+use bytes::Bytes;
+use eventsource_stream::{EventStreamError, Eventsource};
 use futures::{Stream, StreamExt};
-use reqwest_eventsource::{Event, EventSource};
 use serde::de::DeserializeOwned;
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 
-use crate::error::Error;
+use crate::{
+    error::{ApiError, Error},
+    middleware::MiddlewareStack,
+};
 
-pub async fn stream<O: DeserializeOwned + Send + 'static>(
-    mut event_source: EventSource,
+/// Parses the server-sent events of a response body as they are pulled, so a slow consumer applies backpressure to the connection and dropping the stream closes it.
+///
+/// Comments, e.g. OpenRouter's `: OPENROUTER PROCESSING`, and events without data are skipped, as are frames that are not JSON. An `error` object is yielded as [`Error::Api`] and ends the stream, as does a transport error. The stream ends after `stream_done_message`; when the provider closes the connection before sending it, the stream ends with an [`Error::Stream`] instead, as the response may be cut off. It is never reopened.
+pub fn stream<O: DeserializeOwned + Send + 'static>(
+    body: Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>,
     stream_done_message: &'static str,
-) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
-    let (tx, rx) = mpsc::unbounded_channel();
-
-    tokio::spawn(async move {
-        while let Some(event) = event_source.next().await {
-            match event {
-                Err(e) => {
-                    if tx.send(Err(Error::Stream(e.to_string()))).is_err() {
-                        break;
-                    }
+    middleware: MiddlewareStack,
+) -> Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>> {
+    let events = body.eventsource();
+    let stream = futures::stream::unfold(Some((events, middleware)), move |state| async move {
+        let (mut events, middleware) = state?;
+        loop {
+            let event = match events.next().await {
+                Some(Ok(event)) => event,
+                None => {
+                    let error =
+                        Error::Stream(format!("connection closed before {stream_done_message}"));
+                    return Some((Err(error), None));
                 }
-                Ok(event) => match event {
-                    Event::Open => continue,
-                    Event::Message(event) => {
-                        if event.data == stream_done_message {
-                            break;
-                        }
-
-                        let output: Result<O, Error> =
-                            serde_json::from_str::<O>(&event.data).map_err(|e| e.into());
-                        if tx.send(output).is_err() {
-                            break;
-                        }
-                    }
-                },
+                Some(Err(EventStreamError::Transport(e))) => return Some((Err(e), None)),
+                Some(Err(e)) => {
+                    let error = Error::Stream(format!("Failed to read event stream. Error = {e}"));
+                    return Some((Err(error), None));
+                }
+            };
+            let data = event.data.trim();
+            if data == stream_done_message {
+                return None;
+            }
+            if !data.starts_with(['{', '[']) {
+                tracing::debug!(event = %event.event, data, "skipped non-JSON event");
+                continue;
+            }
+            let mut value: serde_json::Value = match serde_json::from_str(data) {
+                Ok(value) => value,
+                Err(e) => return Some((Err(e.into()), Some((events, middleware)))),
+            };
+            if event.event == "error" || value.get("error").is_some_and(|e| !e.is_null()) {
+                let error = Error::Api(Box::new(ApiError::from_body(None, data)));
+                return Some((Err(error), None));
             }
+            if let Err(e) = middleware.on_stream_event(&mut value) {
+                return Some((Err(e), None));
+            }
+            let output = serde_json::from_value::<O>(value).map_err(Error::from);
+            return Some((output, Some((events, middleware))));
         }
-        event_source.close();
     });
+    Box::pin(stream)
+}
+
+/// Ends the stream with [`Error::Cancelled`] when the token is cancelled, dropping the inner stream and its connection right away.
+pub fn cancellable<T: Send + 'static>(
+    stream: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>,
+    cancellation: CancellationToken,
+) -> Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>> {
+    Box::pin(Cancellable {
+        inner: Some(stream),
+        cancelled: Box::pin(cancellation.cancelled_owned()),
+    })
+}
+
+struct Cancellable<S> {
+    inner: Option<S>,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+}
+
+impl<S, T> Stream for Cancellable<S>
+where
+    S: Stream<Item = Result<T, Error>> + Unpin,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+        if self.cancelled.as_mut().poll(cx).is_ready() {
+            self.inner = None;
+            return Poll::Ready(Some(Err(Error::Cancelled)));
+        }
+        let item = self.inner.as_mut().map(|inner| inner.poll_next_unpin(cx));
+        if let Some(Poll::Ready(None)) = item {
+            self.inner = None;
+        }
+        item.unwrap_or(Poll::Ready(None))
+    }
+}
+
+/// Fails with [`Error::Cancelled`] when the token is cancelled, dropping the request future right away.
+pub(crate) async fn with_cancellation<T>(
+    future: impl Future<Output = Result<T, Error>>,
+    cancellation: Option<&CancellationToken>,
+) -> Result<T, Error> {
+    let Some(cancellation) = cancellation else {
+        return future.await;
+    };
+    tokio::select! {
+        _ = cancellation.cancelled() => Err(Error::Cancelled),
+        output = future => output,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stream_works() {
+        let body = [
+            ": OPENROUTER PROCESSING\n\n",
+            "data: {\"id\": 1}\n\ndata: ping\n\n",
+            "data:\n\nevent: message\ndata: {\"id\": 2}\n",
+            "\ndata: {\"error\": {\"message\": \"Provider returned error\", \"code\": 502}}\n\n",
+            "data: {\"id\": 3}\n\n",
+        ]
+        .map(|chunk| Ok(Bytes::from(chunk)));
+        let events: Vec<Result<serde_json::Value, Error>> = stream(
+            Box::pin(futures::stream::iter(body)),
+            "[DONE]",
+            MiddlewareStack::new(),
+        )
+        .collect()
+        .await;
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].as_ref().unwrap()["id"], 2);
+        match &events[2] {
+            Err(Error::Api(error)) => {
+                assert_eq!(error.message, "Provider returned error");
+                assert!(error.is_retryable());
+            }
+            other => panic!("expected an api error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_done_works() {
+        let body = |chunks: &'static [&'static str]| {
+            let chunks = chunks.iter().map(|chunk| Ok(Bytes::from(*chunk)));
+            Box::pin(futures::stream::iter(chunks))
+        };
+        let events: Vec<Result<serde_json::Value, Error>> = stream(
+            body(&["data: {\"id\": 1}\n\n", "data: [DONE]\n\n"]),
+            "[DONE]",
+            MiddlewareStack::new(),
+        )
+        .collect()
+        .await;
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_ok());
+
+        let events: Vec<Result<serde_json::Value, Error>> = stream(
+            body(&["data: {\"id\": 1}\n\n", "data: {\"id\""]),
+            "[DONE]",
+            MiddlewareStack::new(),
+        )
+        .collect()
+        .await;
+        assert_eq!(events.len(), 2);
+        match &events[1] {
+            Err(Error::Stream(message)) => assert_eq!(message, "connection closed before [DONE]"),
+            other => panic!("expected a stream error, got {other:?}"),
+        }
+    }
 
-    Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+    #[tokio::test]
+    async fn cancellable_stream_works() {
+        let cancellation = CancellationToken::new();
+        let inner = futures::stream::iter([Ok(1), Ok(2)]).chain(futures::stream::pending());
+        let mut stream = cancellable(Box::pin(inner), cancellation.clone());
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        assert_eq!(stream.next().await.unwrap().unwrap(), 2);
+        cancellation.cancel();
+        assert!(matches!(stream.next().await, Some(Err(Error::Cancelled))));
+        assert!(stream.next().await.is_none());
+    }
 }
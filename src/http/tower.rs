@@ -0,0 +1,359 @@
+use std::{fmt::Debug, pin::Pin, time::Instant};
+
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use http_body::Body;
+use http_body_util::{BodyExt, Full};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+    multipart::Form,
+    Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tower::{BoxError, Service, ServiceExt};
+
+use crate::{
+    error::Error, middleware::MiddlewareStack, providers::Config, rate_limit::RateLimiter,
+};
+
+use super::{stream::stream, HttpBody, HttpClient, HttpRequest, HttpResponse, ResponseMeta};
+
+/// An [`HttpClient`] backed by a [`tower::Service`], so existing tower layers (timeout, concurrency limit, retry, tracing) and transports (hyper, an in-process mock) can be reused.
+///
+/// Multipart requests, e.g. file uploads, are read into memory before they are sent, as the service takes a [`Full`] body.
+#[derive(Clone)]
+pub struct TowerHttpClient<S, C: Config> {
+    pub(crate) service: S,
+    pub(crate) config: C,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) middleware: MiddlewareStack,
+}
+
+impl<S, C: Config> Debug for TowerHttpClient<S, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TowerHttpClient")
+            .field("config", &self.config)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("middleware", &self.middleware)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, C: Config> TowerHttpClient<S, C> {
+    pub fn new(service: S, config: C) -> Self {
+        Self {
+            service,
+            config,
+            rate_limiter: None,
+            middleware: MiddlewareStack::new(),
+        }
+    }
+}
+
+impl<S, B, C> TowerHttpClient<S, C>
+where
+    S: Service<http::Request<Full<Bytes>>, Response = http::Response<B>> + Clone + Send + Sync,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    C: Config,
+{
+    /// Sends the request and returns the response, whose body is not read yet, with the request url.
+    async fn execute(
+        &self,
+        mut request: HttpRequest,
+    ) -> Result<(http::Response<B>, String), Error> {
+        self.middleware.on_request(&mut request)?;
+        let (url, mut headers, config_query) = match request.is_absolute() {
+            true => (request.path.clone(), HeaderMap::new(), vec![]),
+            false => (
+                self.config.url(&request.path),
+                self.config.headers()?,
+                self.config.query(),
+            ),
+        };
+        let query = config_query
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .chain(request.query.iter().cloned());
+        let uri = Url::parse_with_params(&url, query).map_err(|e| {
+            Error::InvalidArgument(format!("Invalid url. Error = {e}, url = {url}"))
+        })?;
+
+        let model = request.model().map(str::to_string);
+        let body = match std::mem::take(&mut request.body) {
+            HttpBody::Empty => {
+                headers.remove(CONTENT_TYPE);
+                Bytes::new()
+            }
+            HttpBody::Json(body) => serde_json::to_vec(&body)?.into(),
+            HttpBody::Multipart(form) => multipart_body(form, &mut headers, &url).await?,
+        };
+        headers.extend(request.headers);
+
+        let mut builder = http::Request::builder()
+            .method(request.method)
+            .uri(uri.as_str());
+        if let Some(builder_headers) = builder.headers_mut() {
+            *builder_headers = headers;
+        }
+        let http_request = builder.body(Full::new(body)).map_err(|e| {
+            Error::InvalidArgument(format!("Invalid HTTP request. Error = {e}, url = {url}"))
+        })?;
+
+        let resp = self
+            .service
+            .clone()
+            .oneshot(http_request)
+            .await
+            .map_err(|e| {
+                Error::Transport(format!(
+                    "Failed to send HTTP request. Error = {}, url = {url:?}",
+                    e.into()
+                ))
+            })?;
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.observe(
+                &self.config.provider_name(),
+                model.as_deref().unwrap_or_default(),
+                resp.headers(),
+            );
+        }
+        Ok((resp, url))
+    }
+
+    /// Sends the request and streams the body of a successful response, with the metadata of the response.
+    async fn open_stream(
+        &self,
+        request: HttpRequest,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        let start = Instant::now();
+        let (resp, url) = self.execute(request).await?;
+        let time_to_first_byte = start.elapsed();
+        let (parts, body) = resp.into_parts();
+        if !parts.status.is_success() {
+            let body = match body.collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(_) => Bytes::new(),
+            };
+            return Err(HttpResponse {
+                url,
+                status: parts.status,
+                headers: parts.headers,
+                body,
+                time_to_first_byte: Some(time_to_first_byte),
+                latency: Some(start.elapsed()),
+            }
+            .error());
+        }
+        let meta = ResponseMeta {
+            time_to_first_byte: Some(time_to_first_byte),
+            ..ResponseMeta::new(url.clone(), parts.status, parts.headers)
+        };
+        let body = body
+            .into_data_stream()
+            .map(move |chunk| chunk.map_err(|e| body_error(e, &url)));
+        Ok((Box::pin(body), meta))
+    }
+}
+
+/// Encodes a multipart form with the content type of its boundary.
+async fn multipart_body(form: Form, headers: &mut HeaderMap, url: &str) -> Result<Bytes, Error> {
+    let content_type = format!("multipart/form-data; boundary={}", form.boundary());
+    let content_type = HeaderValue::from_str(&content_type)
+        .map_err(|e| Error::InvalidArgument(format!("Invalid multipart boundary. Error = {e}")))?;
+    headers.insert(CONTENT_TYPE, content_type);
+    let mut body = BytesMut::new();
+    let mut stream = form.into_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            Error::HttpClient(format!(
+                "Failed to read multipart form. Error = {e}, url = {url}"
+            ))
+        })?;
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
+}
+
+fn body_error(e: impl Into<BoxError>, url: &str) -> Error {
+    Error::Transport(format!(
+        "Failed to read bytes from HTTP request. Error = {}, url = {url}",
+        e.into()
+    ))
+}
+
+#[async_trait::async_trait]
+impl<S, B, C> HttpClient for TowerHttpClient<S, C>
+where
+    S: Service<http::Request<Full<Bytes>>, Response = http::Response<B>>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    C: Config,
+{
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let start = Instant::now();
+        let (resp, url) = self.execute(request).await?;
+        let time_to_first_byte = start.elapsed();
+        let (parts, body) = resp.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(|e| body_error(e, &url))?
+            .to_bytes();
+        let mut response = HttpResponse {
+            url,
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            time_to_first_byte: Some(time_to_first_byte),
+            latency: Some(start.elapsed()),
+        };
+        self.middleware.on_response(&mut response)?;
+        Ok(response)
+    }
+
+    async fn send_stream(
+        &self,
+        request: HttpRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>, Error> {
+        Ok(self.open_stream(request).await?.0)
+    }
+
+    async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+        request: I,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+        Ok(self.post_stream_with_meta(path, request).await?.0)
+    }
+
+    async fn post_stream_with_meta<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+        request: I,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        let request = HttpRequest::post(path)
+            .with_json(request)?
+            .with_header("Accept", "text/event-stream")?;
+        let (body, meta) = self.open_stream(request).await?;
+        let stream = stream(
+            body,
+            self.config.stream_done_message(),
+            self.middleware.clone(),
+        );
+        Ok((stream, meta))
+    }
+
+    fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    fn with_middleware(mut self, middleware: MiddlewareStack) -> Self {
+        self.middleware = middleware;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tower::service_fn;
+
+    use super::*;
+    use crate::{providers::OpenAIConfig, Client, OpenAIProvider};
+
+    #[tokio::test]
+    async fn tower_http_client_works() {
+        let service = service_fn(|request: http::Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/v1/chat/completions");
+            let body = request.into_body().collect().await?.to_bytes();
+            let body: serde_json::Value = serde_json::from_slice(&body)?;
+            let response = json!({
+                "id": "1",
+                "model": body["model"],
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}}]
+            });
+            Ok::<_, BoxError>(http::Response::new(Full::new(Bytes::from(
+                response.to_string(),
+            ))))
+        });
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(
+            OpenAIProvider::new(config.clone()),
+            TowerHttpClient::new(service, config),
+        );
+
+        let mut chat = client.chat_service();
+        let request = crate::ChatRequest::new("gpt-4o-mini", vec![crate::ChatMessage::user("Hi")]);
+        let response = chat.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(response.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(
+            response.choices[0]
+                .message
+                .as_ref()
+                .unwrap()
+                .content
+                .as_deref(),
+            Some("Hi")
+        );
+    }
+
+    #[tokio::test]
+    async fn tower_http_client_multipart_works() {
+        let service = service_fn(|request: http::Request<Full<Bytes>>| async move {
+            assert_eq!(request.uri().path(), "/v1/files");
+            let content_type = request.headers()[CONTENT_TYPE].to_str()?.to_string();
+            let boundary = content_type
+                .strip_prefix("multipart/form-data; boundary=")
+                .unwrap()
+                .to_string();
+            let body = request.into_body().collect().await?.to_bytes();
+            let body = String::from_utf8(body.to_vec())?;
+            assert!(body.contains(&format!("--{boundary}")));
+            assert!(body.contains("name=\"purpose\"\r\n\r\nbatch"));
+            assert!(body.contains("filename=\"input.jsonl\""));
+            assert!(body.contains("{\"custom_id\": \"1\"}"));
+            let response = json!({"id": "file-1", "object": "file", "purpose": "batch"});
+            Ok::<_, BoxError>(http::Response::new(Full::new(Bytes::from(
+                response.to_string(),
+            ))))
+        });
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(
+            OpenAIProvider::new(config.clone()),
+            TowerHttpClient::new(service, config),
+        );
+
+        let file = client
+            .files()
+            .upload_bytes(
+                "input.jsonl",
+                "{\"custom_id\": \"1\"}",
+                crate::types::FilePurpose::Batch,
+            )
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file-1");
+    }
+}
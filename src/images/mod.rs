@@ -0,0 +1,148 @@
+use crate::{
+    error::Error,
+    http::{HttpClient, HttpRequest},
+    Client, Provider,
+};
+
+pub mod request;
+pub mod response;
+
+pub use request::*;
+pub use response::*;
+
+#[derive(Debug, Clone)]
+pub struct Images<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Images<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self { client }
+    }
+
+    /// Creates images from a prompt.
+    pub async fn generate(&self, request: ImageRequest) -> Result<ImagesResponse, Error> {
+        let output_format = request.output_format;
+        let mut response: ImagesResponse = self
+            .client
+            .http_client
+            .post("/images/generations", request)
+            .await?;
+        response.output_format = response.output_format.or(output_format);
+        Ok(response)
+    }
+
+    /// Edits an image, optionally only in the transparent areas of a mask.
+    pub async fn edit(&self, request: ImageEditRequest) -> Result<ImagesResponse, Error> {
+        if request.prompt.is_none() {
+            return Err(Error::InvalidArgument(
+                "An image edit requires a prompt, use images.variation instead".into(),
+            ));
+        }
+        let output_format = request.output_format;
+        let form = request.into_form().await?;
+        let mut response: ImagesResponse = self
+            .client
+            .http_client
+            .request_json(HttpRequest::post("/images/edits").with_multipart(form))
+            .await?;
+        response.output_format = response.output_format.or(output_format);
+        Ok(response)
+    }
+
+    /// Creates variations of an image. The prompt and mask of the request are ignored.
+    pub async fn variation(&self, mut request: ImageEditRequest) -> Result<ImagesResponse, Error> {
+        request.prompt = None;
+        let form = request.into_form().await?;
+        self.client
+            .http_client
+            .request_json(HttpRequest::post("/images/variations").with_multipart(form))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use bytes::Bytes;
+    use futures::Stream;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::*;
+    use crate::{http::HttpResponse, middleware::MiddlewareStack, rate_limit::RateLimiter};
+
+    /// Answers every request with its path as the body.
+    #[derive(Debug, Clone)]
+    struct EchoHttpClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for EchoHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            Ok(HttpResponse {
+                url: request.path.clone(),
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from(request.path),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not supported".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn image_works() {
+        let request = ImageRequest::new("a cat")
+            .with_size(ImageSize::S1024x1024)
+            .with_response_format(ImageResponseFormat::B64Json);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"prompt": "a cat", "size": "1024x1024", "response_format": "b64_json"})
+        );
+
+        let response: ImagesResponse =
+            serde_json::from_str(r#"{"created": 1713833628, "data": [{"b64_json": "iVBORw0K"}]}"#)
+                .unwrap();
+        let bytes = response.data[0].bytes(&EchoHttpClient).await.unwrap();
+        assert_eq!(&bytes[..4], b"\x89PNG");
+    }
+
+    #[tokio::test]
+    async fn image_download_works() {
+        let url = "https://images.example.com/cat.webp";
+        let mut response: ImagesResponse = serde_json::from_value(serde_json::json!({
+            "created": 1713833628,
+            "data": [{"url": url}]
+        }))
+        .unwrap();
+        assert_eq!(response.data[0].bytes(&EchoHttpClient).await.unwrap(), url);
+
+        response.output_format = Some(ImageOutputFormat::Webp);
+        let dir = std::env::temp_dir().join("async-llm-image-download-works");
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let paths = response
+            .save_all(&EchoHttpClient, &dir, "cat")
+            .await
+            .unwrap();
+        assert_eq!(paths, vec![dir.join("cat-0.webp")]);
+        assert_eq!(tokio::fs::read(&paths[0]).await.unwrap(), url.as_bytes());
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,309 @@
+use reqwest::multipart::Form;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, files::FileInput};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImageSize {
+    #[serde(rename = "256x256")]
+    S256x256,
+    #[serde(rename = "512x512")]
+    S512x512,
+    #[serde(rename = "1024x1024")]
+    S1024x1024,
+    #[serde(rename = "1792x1024")]
+    S1792x1024,
+    #[serde(rename = "1024x1792")]
+    S1024x1792,
+    #[serde(rename = "1536x1024")]
+    S1536x1024,
+    #[serde(rename = "1024x1536")]
+    S1024x1536,
+    #[serde(rename = "auto")]
+    Auto,
+    /// A size unknown to this crate, e.g. `768x768` of a Stable Diffusion wrapper.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl ImageSize {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ImageSize::S256x256 => "256x256",
+            ImageSize::S512x512 => "512x512",
+            ImageSize::S1024x1024 => "1024x1024",
+            ImageSize::S1792x1024 => "1792x1024",
+            ImageSize::S1024x1792 => "1024x1792",
+            ImageSize::S1536x1024 => "1536x1024",
+            ImageSize::S1024x1536 => "1024x1536",
+            ImageSize::Auto => "auto",
+            ImageSize::Other(size) => size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageQuality {
+    /// `dall-e-3` only.
+    Standard,
+    /// `dall-e-3` only.
+    Hd,
+    /// `gpt-image-1` only.
+    Low,
+    /// `gpt-image-1` only.
+    Medium,
+    /// `gpt-image-1` only.
+    High,
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageStyle {
+    Vivid,
+    Natural,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    /// A URL valid for 60 minutes.
+    Url,
+    B64Json,
+}
+
+impl ImageResponseFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageResponseFormat::Url => "url",
+            ImageResponseFormat::B64Json => "b64_json",
+        }
+    }
+}
+
+/// The file format of generated images, `gpt-image-1` only. Other models return PNGs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageOutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Png => "png",
+            ImageOutputFormat::Jpeg => "jpeg",
+            ImageOutputFormat::Webp => "webp",
+        }
+    }
+
+    /// The file extension of images in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Jpeg => "jpg",
+            format => format.as_str(),
+        }
+    }
+}
+
+/// A request to `/images/generations`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImageRequest {
+    /// A text description of the desired image(s).
+    pub prompt: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// The number of images to generate. `dall-e-3` only supports 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u8>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<ImageQuality>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ImageResponseFormat>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<ImageOutputFormat>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<ImageSize>,
+
+    /// `dall-e-3` only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ImageStyle>,
+
+    /// A unique identifier representing your end-user, which can help to monitor and detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl ImageRequest {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            model: None,
+            n: None,
+            quality: None,
+            response_format: None,
+            output_format: None,
+            size: None,
+            style: None,
+            user: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_n(mut self, n: u8) -> Self {
+        self.n = Some(n);
+        self
+    }
+
+    pub fn with_quality(mut self, quality: ImageQuality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn with_response_format(mut self, response_format: ImageResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: ImageOutputFormat) -> Self {
+        self.output_format = Some(output_format);
+        self
+    }
+
+    pub fn with_size(mut self, size: ImageSize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_style(mut self, style: ImageStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+}
+
+/// A request to `/images/edits`, or to `/images/variations` when `prompt` is `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageEditRequest {
+    /// The image to edit, a square PNG smaller than 4MB. Without a mask it must have transparent areas to edit.
+    pub image: FileInput,
+
+    /// A PNG whose fully transparent areas indicate where `image` should be edited. Ignored by variations.
+    pub mask: Option<FileInput>,
+
+    /// A text description of the desired image(s). Required for edits.
+    pub prompt: Option<String>,
+
+    pub model: Option<String>,
+    pub n: Option<u8>,
+    pub response_format: Option<ImageResponseFormat>,
+    pub output_format: Option<ImageOutputFormat>,
+    pub size: Option<ImageSize>,
+    pub user: Option<String>,
+}
+
+impl ImageEditRequest {
+    /// An edit of `image` described by `prompt`.
+    pub fn edit(image: impl Into<FileInput>, prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: Some(prompt.into()),
+            ..Self::variation(image)
+        }
+    }
+
+    /// Variations of `image`.
+    pub fn variation(image: impl Into<FileInput>) -> Self {
+        Self {
+            image: image.into(),
+            mask: None,
+            prompt: None,
+            model: None,
+            n: None,
+            response_format: None,
+            output_format: None,
+            size: None,
+            user: None,
+        }
+    }
+
+    pub fn with_mask(mut self, mask: impl Into<FileInput>) -> Self {
+        self.mask = Some(mask.into());
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_n(mut self, n: u8) -> Self {
+        self.n = Some(n);
+        self
+    }
+
+    pub fn with_response_format(mut self, response_format: ImageResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: ImageOutputFormat) -> Self {
+        self.output_format = Some(output_format);
+        self
+    }
+
+    pub fn with_size(mut self, size: ImageSize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    pub(crate) async fn into_form(self) -> Result<Form, Error> {
+        let mut form = Form::new().part("image", self.image.into_part().await?);
+        if let Some(prompt) = self.prompt {
+            form = form.text("prompt", prompt);
+            if let Some(mask) = self.mask {
+                form = form.part("mask", mask.into_part().await?);
+            }
+        }
+        if let Some(model) = self.model {
+            form = form.text("model", model);
+        }
+        if let Some(n) = self.n {
+            form = form.text("n", n.to_string());
+        }
+        if let Some(response_format) = self.response_format {
+            form = form.text("response_format", response_format.as_str());
+        }
+        if let Some(output_format) = self.output_format {
+            form = form.text("output_format", output_format.as_str());
+        }
+        if let Some(size) = self.size {
+            form = form.text("size", size.as_str().to_string());
+        }
+        if let Some(user) = self.user {
+            form = form.text("user", user);
+        }
+        Ok(form)
+    }
+}
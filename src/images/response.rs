@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, http::HttpClient};
+
+use super::ImageOutputFormat;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImagesResponse {
+    /// The Unix timestamp (in seconds) of when the images were created.
+    pub created: Option<u64>,
+    pub data: Vec<Image>,
+    /// The file format of the images, returned by `gpt-image-1` or else taken from the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<ImageOutputFormat>,
+}
+
+impl ImagesResponse {
+    /// Saves every image into `dir` as `{prefix}-{index}.{extension}`, e.g. `.webp` for [`ImageOutputFormat::Webp`] and `.png` by default, and returns the paths.
+    pub async fn save_all(
+        &self,
+        http_client: &impl HttpClient,
+        dir: impl AsRef<Path>,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let extension = self
+            .output_format
+            .unwrap_or(ImageOutputFormat::Png)
+            .extension();
+        let mut paths = vec![];
+        for (index, image) in self.data.iter().enumerate() {
+            let path = dir.as_ref().join(format!("{prefix}-{index}.{extension}"));
+            image.save(http_client, &path).await?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// A generated image, either as a URL or base64 encoded depending on the request's `response_format`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Image {
+    pub url: Option<String>,
+    pub b64_json: Option<String>,
+    /// The prompt that was used to generate the image, if it was revised.
+    pub revised_prompt: Option<String>,
+}
+
+impl Image {
+    /// The image content, decoded from `b64_json` or downloaded from `url` with `http_client`, e.g. [`Client::http_client`](crate::Client::http_client).
+    pub async fn bytes(&self, http_client: &impl HttpClient) -> Result<Bytes, Error> {
+        if let Some(b64_json) = &self.b64_json {
+            return STANDARD.decode(b64_json).map(Bytes::from).map_err(|e| {
+                Error::InvalidArgument(format!("Failed to decode b64_json. Error = {e}"))
+            });
+        }
+        let Some(url) = &self.url else {
+            return Err(Error::InvalidArgument(
+                "The image has neither url nor b64_json".into(),
+            ));
+        };
+        http_client.get_bytes(url).await
+    }
+
+    pub async fn save(
+        &self,
+        http_client: &impl HttpClient,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        tokio::fs::write(path, self.bytes(http_client).await?).await?;
+        Ok(())
+    }
+}
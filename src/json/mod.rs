@@ -0,0 +1,5 @@
+pub mod partial;
+pub mod repair;
+
+pub use partial::*;
+pub use repair::*;
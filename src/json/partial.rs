@@ -0,0 +1,388 @@
+use std::{
+    iter::Peekable,
+    marker::PhantomData,
+    pin::Pin,
+    str::Chars,
+    task::{Context, Poll},
+};
+
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+
+use crate::error::Error;
+
+/// Parses the prefix of a JSON document into a best-effort snapshot, e.g. structured output or tool-call arguments that are still being streamed.
+///
+/// Open strings, arrays and objects are closed, a key without a value is dropped, and a truncated number or literal is completed (`1.` as `1`, `tr` as `true`). Text before the first `{` or `[` is skipped. Returns `None` when no value has started yet or the input is not JSON.
+pub fn parse_partial(input: &str) -> Option<Value> {
+    let start = input.find(['{', '['])?;
+    let mut parser = PartialParser {
+        chars: input[start..].chars().peekable(),
+    };
+    parser.value().ok()
+}
+
+#[derive(Debug)]
+enum Partial {
+    /// The input ended before the value started.
+    Eof,
+    Invalid,
+}
+
+struct PartialParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl PartialParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn value(&mut self) -> Result<Value, Partial> {
+        self.skip_whitespace();
+        match self.chars.peek().ok_or(Partial::Eof)? {
+            '{' => self.object(),
+            '[' => self.array(),
+            '"' => self.string().map(|(s, _)| Value::String(s)),
+            't' | 'f' | 'n' => self.literal(),
+            '-' | '0'..='9' => self.number(),
+            _ => Err(Partial::Invalid),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Partial> {
+        self.chars.next();
+        let mut map = Map::new();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None => return Ok(Value::Object(map)),
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Value::Object(map));
+                }
+                Some(',') => {
+                    self.chars.next();
+                    continue;
+                }
+                Some('"') => {}
+                Some(_) => return Err(Partial::Invalid),
+            }
+            let (key, complete) = self.string()?;
+            self.skip_whitespace();
+            if !complete || self.chars.next_if_eq(&':').is_none() {
+                return match self.chars.peek() {
+                    None => Ok(Value::Object(map)),
+                    Some(_) => Err(Partial::Invalid),
+                };
+            }
+            match self.value() {
+                Ok(value) => {
+                    map.insert(key, value);
+                }
+                Err(Partial::Eof) => return Ok(Value::Object(map)),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Partial> {
+        self.chars.next();
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None => return Ok(Value::Array(items)),
+                Some(']') => {
+                    self.chars.next();
+                    return Ok(Value::Array(items));
+                }
+                Some(',') => {
+                    self.chars.next();
+                    continue;
+                }
+                Some(_) => {}
+            }
+            match self.value() {
+                Ok(value) => items.push(value),
+                Err(Partial::Eof) => return Ok(Value::Array(items)),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the string and whether its closing quote was read.
+    fn string(&mut self) -> Result<(String, bool), Partial> {
+        self.chars.next();
+        let mut s = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok((s, true)),
+                '\\' => {
+                    let Some(escaped) = self.chars.next() else {
+                        break;
+                    };
+                    match escaped {
+                        'n' => s.push('\n'),
+                        't' => s.push('\t'),
+                        'r' => s.push('\r'),
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'u' => {
+                            let Some(code) = hex4(&mut self.chars)? else {
+                                break;
+                            };
+                            if (0xD800..0xDC00).contains(&code) {
+                                // A high surrogate is combined with the low surrogate escaped right after it.
+                                let mut lookahead = self.chars.clone();
+                                if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+                                    let Some(low) = hex4(&mut lookahead)? else {
+                                        // The input ends within the low surrogate.
+                                        self.chars = lookahead;
+                                        break;
+                                    };
+                                    if (0xDC00..0xE000).contains(&low) {
+                                        self.chars = lookahead;
+                                        let code =
+                                            0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                                        s.extend(char::from_u32(code));
+                                        continue;
+                                    }
+                                }
+                            }
+                            // An unpaired surrogate, e.g. the first half of a pair cut off by the end of the input, is dropped.
+                            s.extend(char::from_u32(code));
+                        }
+                        other => s.push(other),
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+        Ok((s, false))
+    }
+
+    fn literal(&mut self) -> Result<Value, Partial> {
+        let mut word = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            word.push(c);
+        }
+        let complete = self.chars.peek().is_some();
+        for (literal, value) in [
+            ("true", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+            ("null", Value::Null),
+        ] {
+            if word == literal || (!complete && literal.starts_with(&word)) {
+                return Ok(value);
+            }
+        }
+        Err(Partial::Invalid)
+    }
+
+    fn number(&mut self) -> Result<Value, Partial> {
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(c);
+        }
+        if self.chars.peek().is_none() {
+            // A truncated number, e.g. `-`, `1.` or `1e-`.
+            let trimmed = number.trim_end_matches(|c: char| !c.is_ascii_digit());
+            if trimmed.is_empty() {
+                return Err(Partial::Eof);
+            }
+            number = trimmed.to_string();
+        }
+        serde_json::from_str::<Number>(&number)
+            .map(Value::Number)
+            .map_err(|_| Partial::Invalid)
+    }
+}
+
+/// Accumulates streamed JSON text and keeps the latest best-effort snapshot, see [`parse_partial`].
+#[derive(Debug, Clone, Default)]
+pub struct PartialJson {
+    buffer: String,
+    value: Option<Value>,
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a delta and returns the new snapshot if it changed.
+    pub fn push(&mut self, delta: &str) -> Option<&Value> {
+        self.buffer.push_str(delta);
+        let value = parse_partial(&self.buffer);
+        if value.is_none() || value == self.value {
+            return None;
+        }
+        self.value = value;
+        self.value.as_ref()
+    }
+
+    /// The text received so far.
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// The latest snapshot.
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// Deserializes the latest snapshot. Fields that have not arrived yet are missing, so `T` should make them optional.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<Option<T>, Error> {
+        self.value
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(Error::from)
+    }
+}
+
+/// A stream of partial JSON snapshots, see [`crate::chat::TextStreamExt::partial_json`].
+///
+/// A snapshot is yielded each time it changes. Snapshots that do not deserialize into `T` yet are skipped.
+pub struct PartialJsonStream<S, T = Value> {
+    inner: S,
+    json: PartialJson,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<S, T> PartialJsonStream<S, T> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            json: PartialJson::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The text received so far.
+    pub fn buffer(&self) -> &str {
+        self.json.buffer()
+    }
+}
+
+impl<S, T> Stream for PartialJsonStream<S, T>
+where
+    S: Stream<Item = Result<String, Error>> + Unpin,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(delta))) => {
+                    if this.json.push(&delta).is_none() {
+                        continue;
+                    }
+                    if let Ok(Some(value)) = this.json.parse() {
+                        return Poll::Ready(Some(Ok(value)));
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Reads the 4 hex digits of a `\u` escape, or `None` if the input ends before them.
+fn hex4(chars: &mut Peekable<Chars<'_>>) -> Result<Option<u32>, Partial> {
+    let hex: String = (0..4).map_while(|_| chars.next()).collect();
+    if hex.len() < 4 {
+        return Ok(None);
+    }
+    u32::from_str_radix(&hex, 16)
+        .map(Some)
+        .map_err(|_| Partial::Invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_partial_works() {
+        for (input, expected) in [
+            ("", None),
+            ("Sure! ```json\n{", Some(json!({}))),
+            (r#"{"name": "Ha"#, Some(json!({"name": "Ha"}))),
+            (r#"{"name": "Hanoi", "pop"#, Some(json!({"name": "Hanoi"}))),
+            (
+                r#"{"name": "Hanoi", "pop":"#,
+                Some(json!({"name": "Hanoi"})),
+            ),
+            (r#"{"temp": -3.5e"#, Some(json!({"temp": -3.5}))),
+            (r#"{"ok": tr"#, Some(json!({"ok": true}))),
+            (
+                r#"{"tags": ["a", "b\u00"#,
+                Some(json!({"tags": ["a", "b"]})),
+            ),
+            (
+                r#"[{"a": [1, {"b": null}]}, {"c""#,
+                Some(json!([{"a": [1, {"b": null}]}, {}])),
+            ),
+            (r#"{"a": 1} trailing"#, Some(json!({"a": 1}))),
+            (r#"{"e": "\ud83d\ude00!"}"#, Some(json!({"e": "😀!"}))),
+            (r#"{"e": "a\ud83d\ude"#, Some(json!({"e": "a"}))),
+            (r#"{"e": "a\ud83d"#, Some(json!({"e": "a"}))),
+            (r#"{"e": "a\ud83d\n\ude00"}"#, Some(json!({"e": "a\n"}))),
+            (r#"{"a": x"#, None),
+        ] {
+            assert_eq!(parse_partial(input), expected, "{input}");
+        }
+    }
+
+    #[tokio::test]
+    async fn partial_json_stream_works() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct City {
+            name: Option<String>,
+            population: Option<u64>,
+        }
+
+        let deltas = futures::stream::iter(
+            [
+                r#"{"na"#,
+                r#"me": "Ha"#,
+                r#"noi", "#,
+                r#""population": 8"#,
+                "500000}",
+            ]
+            .map(|delta| Ok(delta.to_string())),
+        );
+        let cities: Vec<City> = PartialJsonStream::new(deltas)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(cities.len(), 5);
+        assert_eq!(
+            cities[0],
+            City {
+                name: None,
+                population: None
+            }
+        );
+        assert_eq!(
+            cities.last(),
+            Some(&City {
+                name: Some("Hanoi".into()),
+                population: Some(8_500_000)
+            })
+        );
+    }
+}
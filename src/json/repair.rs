@@ -0,0 +1,306 @@
+use std::{iter::Peekable, str::Chars};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::Error;
+
+use super::parse_partial;
+
+/// A change made by [`JsonRepair`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairAction {
+    /// Removed a markdown code fence around the JSON.
+    StrippedFences,
+    /// Removed text before or after the JSON.
+    StrippedText,
+    RemovedComments,
+    RemovedTrailingCommas,
+    /// Replaced single-quoted strings with double-quoted ones.
+    ReplacedSingleQuotes,
+    QuotedKeys,
+    /// Replaced `True`, `False`, `None` and `undefined` with JSON literals.
+    ReplacedLiterals,
+    /// Closed the strings, arrays and objects of JSON truncated e.g. at `max_tokens`.
+    ClosedTruncated,
+}
+
+/// What [`JsonRepair`] changed to parse the input. Empty when the input was valid JSON.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    pub actions: Vec<RepairAction>,
+}
+
+impl RepairReport {
+    pub fn is_repaired(&self) -> bool {
+        !self.actions.is_empty()
+    }
+
+    fn push(&mut self, action: RepairAction) {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+    }
+}
+
+/// Parses JSON generated by a model, repairing common mistakes of smaller models: markdown fences, surrounding text, comments, trailing commas, single quotes, unquoted keys, Python literals and truncation.
+///
+/// Valid JSON is parsed as is. In strict mode, repair is disabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonRepair {
+    strict: bool,
+}
+
+impl JsonRepair {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser that rejects invalid JSON instead of repairing it.
+    pub fn strict() -> Self {
+        Self { strict: true }
+    }
+
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, text: &str) -> Result<(T, RepairReport), Error> {
+        let (value, report) = self.parse_value(text)?;
+        Ok((serde_json::from_value(value)?, report))
+    }
+
+    pub fn parse_value(&self, text: &str) -> Result<(Value, RepairReport), Error> {
+        let error = match serde_json::from_str(text) {
+            Ok(value) => return Ok((value, RepairReport::default())),
+            Err(e) => e,
+        };
+        if self.strict {
+            return Err(error.into());
+        }
+
+        let mut report = RepairReport::default();
+        let mut text = text.trim();
+        if let Some(inner) = strip_fences(text) {
+            report.push(RepairAction::StrippedFences);
+            text = inner;
+        }
+        let Some(start) = text.find(['{', '[']) else {
+            return Err(error.into());
+        };
+        if start > 0 {
+            report.push(RepairAction::StrippedText);
+        }
+        let (json, complete) = rewrite(&text[start..], &mut report);
+        let value = match complete {
+            true => serde_json::from_str(&json)?,
+            false => {
+                report.push(RepairAction::ClosedTruncated);
+                parse_partial(&json).ok_or(error)?
+            }
+        };
+        Ok((value, report))
+    }
+
+    /// Parses the arguments of a function call. Empty arguments are parsed as `{}`.
+    pub(crate) fn parse_arguments<T: DeserializeOwned>(
+        &self,
+        arguments: &str,
+    ) -> Result<(T, RepairReport), Error> {
+        match arguments.trim().is_empty() {
+            true => self.parse("{}"),
+            false => self.parse(arguments),
+        }
+    }
+}
+
+/// Parses JSON generated by a model with the default [`JsonRepair`].
+pub fn parse_json<T: DeserializeOwned>(text: &str) -> Result<T, Error> {
+    JsonRepair::new().parse(text).map(|(value, _)| value)
+}
+
+/// Returns the content of the first markdown code fence, or of an unclosed one.
+fn strip_fences(text: &str) -> Option<&str> {
+    let start = text.find("```")?;
+    let inner = &text[start + 3..];
+    // Skip the language tag, e.g. ```json
+    let inner = &inner[inner.find('\n').map(|i| i + 1).unwrap_or(0)..];
+    Some(inner.find("```").map(|end| &inner[..end]).unwrap_or(inner))
+}
+
+/// Rewrites the text from the first `{` or `[` into JSON. Returns whether the top-level value was closed.
+fn rewrite(input: &str, report: &mut RepairReport) -> (String, bool) {
+    let mut out = String::with_capacity(input.len());
+    let mut depth = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push('"');
+                if !copy_string(&mut chars, &mut out) {
+                    return (out, false);
+                }
+            }
+            '\'' => {
+                report.push(RepairAction::ReplacedSingleQuotes);
+                out.push('"');
+                if !copy_single_quoted(&mut chars, &mut out) {
+                    return (out, false);
+                }
+            }
+            '/' if matches!(chars.peek(), Some('/' | '*')) => {
+                report.push(RepairAction::RemovedComments);
+                match chars.next() {
+                    Some('/') => while chars.next_if(|c| *c != '\n').is_some() {},
+                    _ => {
+                        let mut previous = ' ';
+                        for c in chars.by_ref() {
+                            if previous == '*' && c == '/' {
+                                break;
+                            }
+                            previous = c;
+                        }
+                    }
+                }
+            }
+            '{' | '[' => {
+                depth.push(c);
+                out.push(c);
+            }
+            '}' | ']' => {
+                let end = out.trim_end().len();
+                if out[..end].ends_with(',') {
+                    report.push(RepairAction::RemovedTrailingCommas);
+                    out.truncate(end - 1);
+                }
+                out.push(c);
+                depth.pop();
+                if depth.is_empty() {
+                    if chars.any(|c| !c.is_whitespace()) {
+                        report.push(RepairAction::StrippedText);
+                    }
+                    return (out, true);
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '$'))
+                {
+                    word.push(c);
+                }
+                let is_key = depth.last() == Some(&'{')
+                    && chars.clone().find(|c| !c.is_whitespace()) == Some(':');
+                if is_key {
+                    report.push(RepairAction::QuotedKeys);
+                    out.push_str(&format!("\"{word}\""));
+                    continue;
+                }
+                let literal = match word.as_str() {
+                    "True" => "true",
+                    "False" => "false",
+                    "None" | "undefined" => "null",
+                    _ => {
+                        out.push_str(&word);
+                        continue;
+                    }
+                };
+                report.push(RepairAction::ReplacedLiterals);
+                out.push_str(literal);
+            }
+            c => out.push(c),
+        }
+    }
+    (out, false)
+}
+
+/// Copies a double-quoted string after its opening quote. Returns whether it was closed.
+fn copy_string(chars: &mut Peekable<Chars>, out: &mut String) -> bool {
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '"' => return true,
+            '\\' => match chars.next() {
+                Some(escaped) => out.push(escaped),
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Copies a single-quoted string after its opening quote as a double-quoted one. Returns whether it was closed.
+fn copy_single_quoted(chars: &mut Peekable<Chars>, out: &mut String) -> bool {
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                out.push('"');
+                return true;
+            }
+            '"' => out.push_str("\\\""),
+            '\\' => match chars.next() {
+                Some('\'') => out.push('\''),
+                Some(escaped) => {
+                    out.push('\\');
+                    out.push(escaped);
+                }
+                None => return false,
+            },
+            c => out.push(c),
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use RepairAction::*;
+
+    #[test]
+    fn json_repair_works() {
+        for (input, expected, actions) in [
+            (r#"{"a": [1, 2]}"#, json!({"a": [1, 2]}), vec![]),
+            (
+                "Here you go:\n```json\n{\"a\": 1,}\n```\nHope it helps!",
+                json!({"a": 1}),
+                vec![StrippedFences, RemovedTrailingCommas],
+            ),
+            (
+                "{'name': 'It\\'s \"fine\"', ok: True, // note\n 'v': None}",
+                json!({"name": "It's \"fine\"", "ok": true, "v": null}),
+                vec![
+                    ReplacedSingleQuotes,
+                    QuotedKeys,
+                    ReplacedLiterals,
+                    RemovedComments,
+                ],
+            ),
+            (
+                r#"{"items": [{"id": 1}, {"id": 2, "name": "tru"#,
+                json!({"items": [{"id": 1}, {"id": 2, "name": "tru"}]}),
+                vec![ClosedTruncated],
+            ),
+            (r#"Result: [1, 2] done"#, json!([1, 2]), vec![StrippedText]),
+        ] {
+            let (value, report) = JsonRepair::new().parse_value(input).unwrap();
+            assert_eq!(value, expected, "{input}");
+            assert_eq!(report.actions, actions, "{input}");
+        }
+    }
+
+    #[test]
+    fn json_repair_strict_works() {
+        assert!(JsonRepair::strict().parse_value("{\"a\": 1,}").is_err());
+        assert!(JsonRepair::new().parse_value("no json here").is_err());
+        let (value, _) = JsonRepair::new().parse_arguments::<Value>(" ").unwrap();
+        assert_eq!(value, json!({}));
+    }
+}
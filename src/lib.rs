@@ -1,15 +1,32 @@
+pub mod audio;
+pub mod batches;
 pub mod chat;
 pub mod client;
 pub mod completions;
+pub mod context;
+pub mod conversation;
 pub mod error;
+pub mod files;
 pub mod http;
+pub mod images;
+pub mod json;
+pub mod middleware;
+pub mod moderations;
 pub mod providers;
+pub mod rate_limit;
+#[cfg(feature = "realtime")]
+pub mod realtime;
 pub mod request;
 pub mod response;
+pub mod responses;
+pub mod telemetry;
+pub mod tokenizer;
 pub mod types;
+pub mod usage;
 
 pub use client::Client;
-pub use error::Error;
+pub use conversation::Conversation;
+pub use error::{ApiError, Error};
 pub use providers::{OpenAIProvider, Provider, RawProvider};
 pub use request::{ChatMessage, ChatRequest};
 pub use response::{ChatResponse, ChatResponseStream};
@@ -0,0 +1,160 @@
+use std::{fmt::Debug, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    http::{HttpRequest, HttpResponse},
+};
+
+/// Inspects and modifies the requests and responses of the http client, e.g. to add trace headers, inject default parameters, redact content, log payloads or patch provider quirks.
+///
+/// All methods do nothing by default. An error fails the call.
+pub trait Middleware: Debug + Send + Sync {
+    /// Called before a request is sent. JSON bodies, e.g. a chat request, are available with [`HttpRequest::json_mut`].
+    fn on_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called when the whole response is received, whatever its status code.
+    fn on_response(&self, response: &mut HttpResponse) -> Result<(), Error> {
+        let _ = response;
+        Ok(())
+    }
+
+    /// Called for each JSON event of a stream, e.g. a chat chunk, before it is parsed.
+    fn on_stream_event(&self, event: &mut Value) -> Result<(), Error> {
+        let _ = event;
+        Ok(())
+    }
+}
+
+/// An ordered stack of middlewares. Requests go through them in the order they were added, responses and stream events in reverse order.
+#[derive(Debug, Clone, Default)]
+pub struct MiddlewareStack {
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl MiddlewareStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.push(middleware);
+        self
+    }
+
+    pub fn push(&mut self, middleware: impl Middleware + 'static) {
+        self.layers.push(Arc::new(middleware));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn on_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+        self.layers
+            .iter()
+            .try_for_each(|layer| layer.on_request(request))
+    }
+
+    pub fn on_response(&self, response: &mut HttpResponse) -> Result<(), Error> {
+        self.layers
+            .iter()
+            .rev()
+            .try_for_each(|layer| layer.on_response(response))
+    }
+
+    pub fn on_stream_event(&self, event: &mut Value) -> Result<(), Error> {
+        self.layers
+            .iter()
+            .rev()
+            .try_for_each(|layer| layer.on_stream_event(event))
+    }
+}
+
+/// Adds parameters that the request body does not set, e.g. a default `temperature` or provider-specific options.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultParams {
+    params: serde_json::Map<String, Value>,
+}
+
+impl DefaultParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl Middleware for DefaultParams {
+    fn on_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+        if let Some(Value::Object(body)) = request.json_mut() {
+            for (name, value) in &self.params {
+                body.entry(name.as_str()).or_insert_with(|| value.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct TraceHeader;
+
+    impl Middleware for TraceHeader {
+        fn on_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+            request
+                .headers
+                .insert("x-trace-id", "trace-1".parse().unwrap());
+            Ok(())
+        }
+
+        fn on_stream_event(&self, event: &mut Value) -> Result<(), Error> {
+            if let Some(content) = event.pointer_mut("/choices/0/delta/content") {
+                *content = content
+                    .as_str()
+                    .unwrap_or_default()
+                    .replace("secret", "***")
+                    .into();
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn middleware_stack_works() {
+        let stack = MiddlewareStack::new().with(TraceHeader).with(
+            DefaultParams::new()
+                .with("temperature", 0.2)
+                .with("model", "x"),
+        );
+        let mut request = HttpRequest::post("/chat/completions")
+            .with_json(json!({"model": "gpt-4o-mini"}))
+            .unwrap();
+        stack.on_request(&mut request).unwrap();
+        assert_eq!(request.headers["x-trace-id"], "trace-1");
+        assert_eq!(
+            request.json_mut().cloned(),
+            Some(json!({"model": "gpt-4o-mini", "temperature": 0.2}))
+        );
+
+        let mut event = json!({"choices": [{"delta": {"content": "my secret"}}]});
+        stack.on_stream_event(&mut event).unwrap();
+        assert_eq!(event["choices"][0]["delta"]["content"], "my ***");
+    }
+}
@@ -0,0 +1,394 @@
+use std::{collections::HashMap, fmt::Debug, pin::Pin, sync::Arc};
+
+use futures::{Stream, StreamExt};
+
+use crate::{error::Error, http::HttpClient, response::Respondable};
+
+use super::{ModerationInput, ModerationRequest, ModerationResponse, ModerationResult};
+
+/// Whether the guard screens the user input or the assistant output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationStage {
+    Input,
+    Output,
+}
+
+/// What the guard does with flagged content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardAction {
+    /// Fail the call with [`Error::Moderation`].
+    Reject,
+    /// Let the call through and notify the callback of [`ModerationGuard::on_flag`].
+    Flag,
+}
+
+/// Passed to the flag callback for flagged content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModerationVerdict {
+    pub stage: ModerationStage,
+    /// The categories over their threshold with their score.
+    pub categories: Vec<(String, f64)>,
+    pub result: ModerationResult,
+}
+
+type FlagCallback = Arc<dyn Fn(&ModerationVerdict) + Send + Sync>;
+
+/// Screens chat and Responses API calls with the moderations endpoint. Attach it with [`crate::Client::with_moderation_guard`].
+///
+/// Without thresholds, content is flagged when the moderation model flags it. The output of streamed responses is screened once the stream ends.
+#[derive(Clone)]
+pub struct ModerationGuard {
+    pub model: Option<String>,
+    pub action: GuardAction,
+    pub input: bool,
+    pub output: bool,
+    pub thresholds: HashMap<String, f64>,
+    pub default_threshold: Option<f64>,
+    on_flag: Option<FlagCallback>,
+}
+
+impl Debug for ModerationGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModerationGuard")
+            .field("model", &self.model)
+            .field("action", &self.action)
+            .field("input", &self.input)
+            .field("output", &self.output)
+            .field("thresholds", &self.thresholds)
+            .field("default_threshold", &self.default_threshold)
+            .finish()
+    }
+}
+
+impl ModerationGuard {
+    /// A guard that screens the user input only.
+    pub fn new(action: GuardAction) -> Self {
+        Self {
+            model: None,
+            action,
+            input: true,
+            output: false,
+            thresholds: HashMap::new(),
+            default_threshold: None,
+            on_flag: None,
+        }
+    }
+
+    pub fn reject() -> Self {
+        Self::new(GuardAction::Reject)
+    }
+
+    pub fn flag() -> Self {
+        Self::new(GuardAction::Flag)
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_input(mut self, input: bool) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn with_output(mut self, output: bool) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Flags content whose score of `category` is at least `threshold`.
+    pub fn with_threshold(mut self, category: impl Into<String>, threshold: f64) -> Self {
+        self.thresholds.insert(category.into(), threshold);
+        self
+    }
+
+    /// The threshold of every category without a specific threshold.
+    pub fn with_default_threshold(mut self, threshold: f64) -> Self {
+        self.default_threshold = Some(threshold);
+        self
+    }
+
+    /// Called for flagged content, with both actions.
+    pub fn on_flag(
+        mut self,
+        callback: impl Fn(&ModerationVerdict) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_flag = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn checks(&self, stage: ModerationStage) -> bool {
+        match stage {
+            ModerationStage::Input => self.input,
+            ModerationStage::Output => self.output,
+        }
+    }
+
+    /// The categories of a result that violate the guard's thresholds, or `None` if it passes.
+    pub fn judge(
+        &self,
+        stage: ModerationStage,
+        result: &ModerationResult,
+    ) -> Option<ModerationVerdict> {
+        let mut categories: Vec<(String, f64)> =
+            if self.thresholds.is_empty() && self.default_threshold.is_none() {
+                result
+                    .categories
+                    .iter()
+                    .filter(|(_, flagged)| **flagged)
+                    .map(|(category, _)| {
+                        let score = result.category_scores.get(category).copied();
+                        (category.clone(), score.unwrap_or(1.0))
+                    })
+                    .collect()
+            } else {
+                result
+                    .category_scores
+                    .iter()
+                    .filter(|(category, score)| {
+                        self.thresholds
+                            .get(*category)
+                            .copied()
+                            .or(self.default_threshold)
+                            .is_some_and(|threshold| **score >= threshold)
+                    })
+                    .map(|(category, score)| (category.clone(), *score))
+                    .collect()
+            };
+        let flagged = match self.thresholds.is_empty() && self.default_threshold.is_none() {
+            true => result.flagged,
+            false => !categories.is_empty(),
+        };
+        if !flagged {
+            return None;
+        }
+        categories.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Some(ModerationVerdict {
+            stage,
+            categories,
+            result: result.clone(),
+        })
+    }
+
+    /// Moderates the content and applies the guard's action: fails with [`Error::Moderation`] when rejecting, otherwise returns the verdict of flagged content.
+    pub async fn screen(
+        &self,
+        client: &impl HttpClient,
+        stage: ModerationStage,
+        input: ModerationInput,
+    ) -> Result<Option<ModerationVerdict>, Error> {
+        if !self.checks(stage) || input.is_empty() {
+            return Ok(None);
+        }
+        let mut request = ModerationRequest::new(input);
+        request.model = self.model.clone();
+        let response: ModerationResponse = client.post("/moderations", request).await?;
+        let Some(verdict) = response
+            .results
+            .iter()
+            .find_map(|result| self.judge(stage, result))
+        else {
+            return Ok(None);
+        };
+        if let Some(callback) = &self.on_flag {
+            callback(&verdict);
+        }
+        match self.action {
+            GuardAction::Reject => Err(Error::Moderation(format!(
+                "{:?} flagged for {:?}",
+                verdict.stage,
+                verdict
+                    .categories
+                    .iter()
+                    .map(|(category, _)| category.as_str())
+                    .collect::<Vec<_>>()
+            ))),
+            GuardAction::Flag => Ok(Some(verdict)),
+        }
+    }
+
+    /// Yields the chunks of a streamed response, then screens their accumulated text once it ends. When rejecting, the stream ends with [`Error::Moderation`], but every chunk has already reached the consumer by then: discard or retract what was shown on this error.
+    pub fn screen_stream<T, H>(
+        self,
+        client: H,
+        stream: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>
+    where
+        T: Respondable + Send + 'static,
+        H: HttpClient,
+    {
+        let state = (Some(stream), String::new(), self, client);
+        Box::pin(futures::stream::unfold(
+            state,
+            |(mut stream, mut text, guard, client)| async move {
+                let item = match stream.as_mut()?.next().await {
+                    Some(Ok(chunk)) => {
+                        text.push_str(chunk.text().unwrap_or_default());
+                        Ok(chunk)
+                    }
+                    Some(Err(e)) => {
+                        stream = None;
+                        Err(e)
+                    }
+                    None => {
+                        // The inner stream is done and must not be polled again.
+                        stream = None;
+                        let output = std::mem::take(&mut text).into();
+                        guard
+                            .screen(&client, ModerationStage::Output, output)
+                            .await
+                            .err()
+                            .map(Err)?
+                    }
+                };
+                Some((item, (stream, text, guard, client)))
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        http::{HttpBody, HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        rate_limit::RateLimiter,
+    };
+
+    /// Flags moderation inputs that mention `kill` for violence.
+    #[derive(Debug, Clone)]
+    struct MockHttpClient;
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let HttpBody::Json(body) = &request.body else {
+                return Err(Error::InvalidArgument("expected a JSON body".into()));
+            };
+            let flagged = body["input"].as_str().unwrap().contains("kill");
+            let result = json!({
+                "flagged": flagged,
+                "categories": {"violence": flagged},
+                "category_scores": {"violence": if flagged { 0.9 } else { 0.1 }}
+            });
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from(json!({"results": [result]}).to_string()),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            _request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            Err(Error::InvalidArgument("streams are not supported".into()))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    fn chunks(deltas: &[&str]) -> Pin<Box<dyn Stream<Item = Result<Value, Error>> + Send>> {
+        let chunks: Vec<Result<Value, Error>> = deltas
+            .iter()
+            .map(|delta| Ok(json!({"choices": [{"index": 0, "delta": {"content": delta}}]})))
+            .collect();
+        Box::pin(futures::stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn moderation_guard_stream_works() {
+        let guard = ModerationGuard::reject().with_output(true);
+        let items: Vec<_> = guard
+            .clone()
+            .screen_stream(MockHttpClient, chunks(&["I will ", "help"]))
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(Result::is_ok));
+
+        // Flagged only once the deltas are joined.
+        let items: Vec<_> = guard
+            .screen_stream(MockHttpClient, chunks(&["I will k", "ill"]))
+            .collect()
+            .await;
+        assert_eq!(items.len(), 3);
+        assert!(matches!(items[2], Err(Error::Moderation(_))));
+
+        let flagged = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let on_flag = flagged.clone();
+        let items: Vec<_> = ModerationGuard::flag()
+            .with_output(true)
+            .on_flag(move |_| on_flag.store(true, std::sync::atomic::Ordering::SeqCst))
+            .screen_stream(MockHttpClient, chunks(&["kill"]))
+            .collect()
+            .await;
+        assert_eq!(items.len(), 1);
+        assert!(flagged.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn moderation_guard_event_stream_works() {
+        // The event stream of a response, which panics when polled after its end.
+        let body = [
+            "data: {\"choices\": [{\"index\": 0, \"delta\": {\"content\": \"kill\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        ]
+        .map(|chunk| Ok(Bytes::from(chunk)));
+        let events = crate::http::stream::stream::<Value>(
+            Box::pin(futures::stream::iter(body)),
+            "[DONE]",
+            MiddlewareStack::new(),
+        );
+        let mut stream = ModerationGuard::reject()
+            .with_output(true)
+            .screen_stream(MockHttpClient, events);
+        let mut items = vec![];
+        while let Some(item) = stream.next().await {
+            items.push(item);
+        }
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[1], Err(Error::Moderation(_))));
+    }
+
+    #[test]
+    fn moderation_guard_works() {
+        let result = ModerationResult {
+            flagged: false,
+            categories: [("violence".to_string(), false)].into(),
+            category_scores: [("violence".to_string(), 0.4), ("hate".to_string(), 0.1)].into(),
+            ..Default::default()
+        };
+        assert!(ModerationGuard::reject()
+            .judge(ModerationStage::Input, &result)
+            .is_none());
+
+        let verdict = ModerationGuard::reject()
+            .with_threshold("violence", 0.3)
+            .judge(ModerationStage::Input, &result)
+            .unwrap();
+        assert_eq!(verdict.categories, vec![("violence".to_string(), 0.4)]);
+
+        assert!(ModerationGuard::flag()
+            .with_default_threshold(0.5)
+            .judge(ModerationStage::Output, &result)
+            .is_none());
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{error::Error, http::HttpClient, Client, Provider};
+
+pub mod guard;
+pub mod types;
+
+pub use guard::*;
+pub use types::*;
+
+#[derive(Debug, Clone)]
+pub struct Moderations<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Moderations<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self { client }
+    }
+
+    /// Classifies whether text or images are potentially harmful.
+    pub async fn create(&self, request: ModerationRequest) -> Result<ModerationResponse, Error> {
+        self.client.http_client.post("/moderations", request).await
+    }
+}
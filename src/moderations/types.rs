@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ImageUrl, UserContent, UserContentPart};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum ModerationInput {
+    Text(String),
+    Texts(Vec<String>),
+    /// Text and images, only supported by `omni-moderation` models.
+    Parts(Vec<ModerationInputPart>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationInputPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+impl ModerationInput {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ModerationInput::Text(text) => text.is_empty(),
+            ModerationInput::Texts(texts) => texts.iter().all(String::is_empty),
+            ModerationInput::Parts(parts) => parts.is_empty(),
+        }
+    }
+}
+
+impl From<&str> for ModerationInput {
+    fn from(value: &str) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+impl From<String> for ModerationInput {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&UserContent> for ModerationInput {
+    /// The text and image parts of a user message. Audio is not supported by moderation models and is skipped.
+    fn from(value: &UserContent) -> Self {
+        match value {
+            UserContent::Text(text) => Self::Text(text.clone()),
+            UserContent::Array(parts) => Self::Parts(
+                parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        UserContentPart::Text { text } => {
+                            Some(ModerationInputPart::Text { text: text.clone() })
+                        }
+                        UserContentPart::ImageUrl { image_url } => {
+                            Some(ModerationInputPart::ImageUrl {
+                                image_url: image_url.clone(),
+                            })
+                        }
+                        UserContentPart::Audio { .. } => None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// A request to `/moderations`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModerationRequest {
+    pub input: ModerationInput,
+
+    /// The moderation model, e.g. `omni-moderation-latest` (default) or `text-moderation-latest`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl ModerationRequest {
+    pub fn new(input: impl Into<ModerationInput>) -> Self {
+        Self {
+            input: input.into(),
+            model: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModerationResponse {
+    pub id: Option<String>,
+    pub model: Option<String>,
+    /// One result per input text, or a single result for [`ModerationInput::Parts`].
+    pub results: Vec<ModerationResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModerationResult {
+    /// Whether the model classifies the content as potentially harmful.
+    pub flagged: bool,
+
+    /// Whether each category, e.g. `harassment`, `self-harm/intent` or `violence/graphic`, is violated.
+    pub categories: HashMap<String, bool>,
+
+    /// The score of each category, between 0 and 1.
+    pub category_scores: HashMap<String, f64>,
+
+    /// The input types (`text` or `image`) each category score applies to.
+    pub category_applied_input_types: Option<HashMap<String, Vec<String>>>,
+}
@@ -10,7 +10,7 @@ pub const OPENAI_ORGANIZATION: &str = "OpenAI-Organization";
 pub const OPENAI_PROJECT: &str = "OpenAI-Project";
 pub const OPENAI_BETA: &str = "OpenAI-Beta";
 
-pub trait Config: Debug + Clone + Send + Sync {
+pub trait Config: Debug + Clone + Send + Sync + 'static {
     fn headers(&self) -> Result<HeaderMap, Error>;
     fn url(&self, path: &str) -> String;
     fn query(&self) -> Vec<(&str, &str)>;
@@ -19,6 +19,16 @@ pub trait Config: Debug + Clone + Send + Sync {
 
     fn api_key(&self) -> Option<&SecretString>;
 
+    /// A name identifying the provider, the host of the base url, e.g. `api.openai.com` or `localhost:11434`.
+    fn provider_name(&self) -> String {
+        let base_url = self.base_url();
+        let host = base_url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(base_url);
+        host.split('/').next().unwrap_or(host).to_string()
+    }
+
     fn stream_done_message(&self) -> &'static str {
         "[DONE]"
     }
@@ -2,11 +2,12 @@ use std::{fmt::Debug, pin::Pin};
 
 use async_trait::async_trait;
 use futures::Stream;
+use serde::de::DeserializeOwned;
 
 use crate::{
     completions::{CompletionRequest, CompletionResponse},
-    error::Error,
-    http::HttpClient,
+    error::{ApiError, Error},
+    http::{HttpClient, ResponseMeta},
     request::Requestable,
     response::Respondable,
 };
@@ -22,12 +23,17 @@ pub use raw::RawProvider;
 #[async_trait]
 pub trait Provider: Debug + Send + Sync {
     type Config: Config;
-    type ChatRequest: Requestable;
-    type ChatResponse: Respondable;
-    type ChatResponseStream: Respondable;
+    type ChatRequest: Requestable + Send;
+    type ChatResponse: Respondable + Send;
+    type ChatResponseStream: Respondable + Send + 'static;
 
     fn config(&self) -> &Self::Config;
 
+    /// A name identifying the provider in usage reports and rate limits. Defaults to [`Config::provider_name`].
+    fn name(&self) -> String {
+        self.config().provider_name()
+    }
+
     async fn chat(
         &self,
         client: &impl HttpClient,
@@ -45,4 +51,73 @@ pub trait Provider: Debug + Send + Sync {
         client: &impl HttpClient,
         request: CompletionRequest,
     ) -> Result<CompletionResponse, Error>;
+
+    /// Like [`Provider::chat`], with the metadata of the response. The default implementation returns empty metadata.
+    async fn chat_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: Self::ChatRequest,
+    ) -> Result<(Self::ChatResponse, ResponseMeta), Error> {
+        Ok((self.chat(client, request).await?, ResponseMeta::default()))
+    }
+
+    /// Like [`Provider::completions`], with the metadata of the response. The default implementation returns empty metadata.
+    async fn completions_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: CompletionRequest,
+    ) -> Result<(CompletionResponse, ResponseMeta), Error> {
+        Ok((
+            self.completions(client, request).await?,
+            ResponseMeta::default(),
+        ))
+    }
+
+    /// Like [`Provider::chat_stream`], with the metadata of the response. The default implementation returns empty metadata.
+    async fn chat_stream_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: Self::ChatRequest,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<Self::ChatResponseStream, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        Ok((
+            self.chat_stream(client, request).await?,
+            ResponseMeta::default(),
+        ))
+    }
+}
+
+/// Parses the body of a chat or completion response. Some OpenAI-compatible servers report errors with a success status code and an `error` object instead of `choices`.
+pub(crate) fn parse_choices<O: DeserializeOwned>(value: serde_json::Value) -> Result<O, Error> {
+    let error = value.get("error").filter(|error| !error.is_null());
+    if value.get("choices").is_none() && error.is_some() {
+        return Err(Error::Api(Box::new(ApiError::from_body(
+            None,
+            &value.to_string(),
+        ))));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_choices_works() {
+        let error = json!({"error": {"message": "model not found"}});
+        assert!(matches!(
+            parse_choices::<serde_json::Value>(error),
+            Err(Error::Api(error)) if error.message == "model not found"
+        ));
+        let response = json!({"choices": [], "error": null});
+        assert!(parse_choices::<serde_json::Value>(response).is_ok());
+    }
 }
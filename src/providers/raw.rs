@@ -6,10 +6,10 @@ use futures::Stream;
 use crate::{
     completions::{CompletionRequest, CompletionResponse},
     error::Error,
-    http::HttpClient,
+    http::{HttpClient, ResponseMeta},
 };
 
-use super::{config::OpenAIConfig, Provider};
+use super::{config::OpenAIConfig, parse_choices, Provider};
 
 #[derive(Debug, Clone, Default)]
 pub struct RawProvider {
@@ -38,7 +38,7 @@ impl Provider for RawProvider {
         client: &impl HttpClient,
         request: Self::ChatRequest,
     ) -> Result<Self::ChatResponse, Error> {
-        client.post("/chat/completions", request).await
+        Ok(self.chat_with_meta(client, request).await?.0)
     }
     async fn chat_stream(
         &self,
@@ -49,11 +49,49 @@ impl Provider for RawProvider {
         client.post_stream("/chat/completions", request).await
     }
 
+    async fn chat_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: Self::ChatRequest,
+    ) -> Result<(Self::ChatResponse, ResponseMeta), Error> {
+        let (value, meta) = client
+            .post_with_meta::<_, serde_json::Value>("/chat/completions", request)
+            .await?;
+        Ok((parse_choices(value)?, meta))
+    }
+
+    async fn chat_stream_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: Self::ChatRequest,
+    ) -> Result<
+        (
+            Pin<Box<dyn Stream<Item = Result<Self::ChatResponseStream, Error>> + Send>>,
+            ResponseMeta,
+        ),
+        Error,
+    > {
+        client
+            .post_stream_with_meta("/chat/completions", request)
+            .await
+    }
+
     async fn completions(
         &self,
         client: &impl HttpClient,
         request: CompletionRequest,
     ) -> Result<CompletionResponse, Error> {
-        client.post("/completions", request).await
+        Ok(self.completions_with_meta(client, request).await?.0)
+    }
+
+    async fn completions_with_meta(
+        &self,
+        client: &impl HttpClient,
+        request: CompletionRequest,
+    ) -> Result<(CompletionResponse, ResponseMeta), Error> {
+        let (value, meta) = client
+            .post_with_meta::<_, serde_json::Value>("/completions", request)
+            .await?;
+        Ok((parse_choices(value)?, meta))
     }
 }
@@ -0,0 +1,343 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::header::HeaderMap;
+
+use crate::{context::TokenCounter, tokenizer::ChatTokenCounter};
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Requests and tokens allowed per minute. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_minute: u32, tokens_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: Some(requests_per_minute),
+            tokens_per_minute: Some(tokens_per_minute),
+        }
+    }
+
+    pub fn requests(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: Some(requests_per_minute),
+            tokens_per_minute: None,
+        }
+    }
+
+    pub fn tokens(tokens_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: None,
+            tokens_per_minute: Some(tokens_per_minute),
+        }
+    }
+}
+
+/// The state of one rate limit: the calls of the last minute and what the provider reported in its `x-ratelimit-*` headers.
+#[derive(Debug, Default)]
+struct Bucket {
+    limit: RateLimit,
+    calls: VecDeque<(Instant, u32)>,
+    remaining_requests: Option<(u32, Instant)>,
+    remaining_tokens: Option<(u32, Instant)>,
+}
+
+impl Bucket {
+    /// How long to wait before a call of `tokens` may be sent, or `None` if it may be sent now.
+    fn wait(&mut self, tokens: u32, now: Instant) -> Option<Duration> {
+        while let Some((at, _)) = self.calls.front() {
+            if now.duration_since(*at) < MINUTE {
+                break;
+            }
+            self.calls.pop_front();
+        }
+
+        let mut wait = Duration::ZERO;
+        if let Some(rpm) = self.limit.requests_per_minute {
+            if self.calls.len() >= rpm as usize {
+                let oldest = self.calls[self.calls.len() - rpm as usize].0;
+                wait = wait.max(MINUTE.saturating_sub(now.duration_since(oldest)));
+            }
+        }
+        if let Some(tpm) = self.limit.tokens_per_minute {
+            // A call larger than the whole budget is let through alone rather than blocked forever.
+            // Summed in u64 as estimates saturated to u32::MAX would overflow.
+            let mut used: u64 = self.calls.iter().map(|(_, t)| u64::from(*t)).sum();
+            for (at, call_tokens) in &self.calls {
+                if used + u64::from(tokens) <= u64::from(tpm) {
+                    break;
+                }
+                used -= u64::from(*call_tokens);
+                wait = wait.max(MINUTE.saturating_sub(now.duration_since(*at)));
+            }
+        }
+        if let Some((remaining, reset_at)) = self.remaining_requests {
+            if remaining == 0 && reset_at > now {
+                wait = wait.max(reset_at - now);
+            }
+        }
+        if let Some((remaining, reset_at)) = self.remaining_tokens {
+            if remaining < tokens && reset_at > now {
+                wait = wait.max(reset_at - now);
+            }
+        }
+        (!wait.is_zero()).then_some(wait)
+    }
+
+    fn take(&mut self, tokens: u32, now: Instant) {
+        self.calls.push_back((now, tokens));
+        if let Some((remaining, _)) = &mut self.remaining_requests {
+            *remaining = remaining.saturating_sub(1);
+        }
+        if let Some((remaining, _)) = &mut self.remaining_tokens {
+            *remaining = remaining.saturating_sub(tokens);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    default: Option<RateLimit>,
+    limits: HashMap<String, RateLimit>,
+    buckets: HashMap<String, Bucket>,
+}
+
+impl State {
+    /// Limits are looked up by `provider/model`, then `model`, then `provider`. Calls sharing a limit key share a bucket; the default limit applies per `provider/model`.
+    fn key(&self, provider: &str, model: &str) -> (String, RateLimit) {
+        let qualified = format!("{provider}/{model}");
+        for key in [qualified.as_str(), model, provider] {
+            if let Some(limit) = self.limits.get(key) {
+                return (key.to_string(), *limit);
+            }
+        }
+        (qualified, self.default.unwrap_or_default())
+    }
+
+    fn bucket(&mut self, provider: &str, model: &str) -> &mut Bucket {
+        let (key, limit) = self.key(provider, model);
+        let bucket = self.buckets.entry(key).or_default();
+        bucket.limit = limit;
+        bucket
+    }
+}
+
+/// A client-side limiter for requests and tokens per minute. Calls over the limit wait instead of failing with `429 Too Many Requests`.
+///
+/// Token usage is estimated locally from the prompt plus `max_completion_tokens`. Clones share the same state, so one limiter can throttle all clones of a client.
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+    counter: Arc<dyn TokenCounter>,
+}
+
+impl Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("RateLimiter")
+            .field("default", &state.default)
+            .field("limits", &state.limits)
+            .finish()
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        #[cfg(feature = "tiktoken")]
+        let counter: Arc<dyn TokenCounter> = Arc::new(ChatTokenCounter::new(
+            crate::tokenizer::BpeTokenizer::new(crate::tokenizer::Encoding::O200kBase),
+        ));
+        #[cfg(not(feature = "tiktoken"))]
+        let counter: Arc<dyn TokenCounter> = Arc::new(ChatTokenCounter::default());
+        Self {
+            state: Default::default(),
+            counter,
+        }
+    }
+
+    /// A limiter that only estimates tokens from the character count.
+    pub fn with_char_token_counter(self) -> Self {
+        self.with_token_counter(ChatTokenCounter::default())
+    }
+
+    pub fn with_token_counter(mut self, counter: impl TokenCounter + 'static) -> Self {
+        self.counter = Arc::new(counter);
+        self
+    }
+
+    /// The limit of every provider and model without a specific limit.
+    pub fn with_default(self, limit: RateLimit) -> Self {
+        self.lock().default = Some(limit);
+        self
+    }
+
+    /// The limit of a model, a provider (see [`crate::Provider::name`]) or a `provider/model` pair.
+    pub fn with_limit(self, key: impl Into<String>, limit: RateLimit) -> Self {
+        self.lock().limits.insert(key.into(), limit);
+        self
+    }
+
+    pub fn token_counter(&self) -> &dyn TokenCounter {
+        self.counter.as_ref()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits until a call of `tokens` to the model may be sent and reserves it.
+    pub async fn acquire(&self, provider: &str, model: &str, tokens: u32) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                let bucket = state.bucket(provider, model);
+                let now = Instant::now();
+                match bucket.wait(tokens, now) {
+                    Some(wait) => wait,
+                    None => {
+                        bucket.take(tokens, now);
+                        return;
+                    }
+                }
+            };
+            tracing::debug!("rate limit reached for {provider}/{model}, waiting {wait:?}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Learns the remaining requests and tokens from the `x-ratelimit-*` headers of a response.
+    pub fn observe(&self, provider: &str, model: &str, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let now = Instant::now();
+        let remaining = |remaining: &str, reset: &str| {
+            let remaining = header(remaining)?.parse::<u32>().ok()?;
+            let reset = header(reset).and_then(parse_reset).unwrap_or(MINUTE);
+            Some((remaining, now + reset))
+        };
+        let requests = remaining(
+            "x-ratelimit-remaining-requests",
+            "x-ratelimit-reset-requests",
+        );
+        let tokens = remaining("x-ratelimit-remaining-tokens", "x-ratelimit-reset-tokens");
+        if requests.is_none() && tokens.is_none() {
+            return;
+        }
+        let mut state = self.lock();
+        let bucket = state.bucket(provider, model);
+        if requests.is_some() {
+            bucket.remaining_requests = requests;
+        }
+        if tokens.is_some() {
+            bucket.remaining_tokens = tokens;
+        }
+    }
+}
+
+/// Parses reset durations like `1s`, `6m0s`, `20ms` or `1h2m3.5s`.
+fn parse_reset(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        total += number
+            * match unit {
+                "ms" => 0.001,
+                "s" | "" => 1.0,
+                "m" => 60.0,
+                "h" => 3600.0,
+                _ => return None,
+            };
+        rest = tail;
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reset_works() {
+        assert_eq!(parse_reset("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_reset("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_reset("1x"), None);
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_works() {
+        let limiter = RateLimiter::new()
+            .with_limit("gpt-4o-mini", RateLimit::tokens(100))
+            .with_default(RateLimit::requests(1));
+        limiter.acquire("api.openai.com", "gpt-4o-mini", 60).await;
+
+        let mut state = limiter.lock();
+        let now = Instant::now();
+        assert!(state
+            .bucket("api.openai.com", "gpt-4o-mini")
+            .wait(60, now)
+            .is_some());
+        assert!(state
+            .bucket("api.openai.com", "gpt-4o-mini")
+            .wait(40, now)
+            .is_none());
+        state.bucket("api.openai.com", "gpt-4").take(1, now);
+        assert!(state
+            .bucket("api.openai.com", "gpt-4")
+            .wait(1, now)
+            .is_some());
+    }
+
+    #[test]
+    fn rate_limiter_large_calls_work() {
+        let mut bucket = Bucket {
+            limit: RateLimit::tokens(u32::MAX),
+            ..Default::default()
+        };
+        let now = Instant::now();
+        bucket.take(u32::MAX, now);
+        bucket.take(u32::MAX, now);
+        assert!(bucket.wait(u32::MAX, now).is_some());
+        assert!(bucket.wait(u32::MAX, now + MINUTE).is_none());
+    }
+
+    #[test]
+    fn rate_limiter_observe_works() {
+        let limiter = RateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "2s".parse().unwrap());
+        limiter.observe("api.openai.com", "gpt-4o-mini", &headers);
+
+        let wait = limiter
+            .lock()
+            .bucket("api.openai.com", "gpt-4o-mini")
+            .wait(1, Instant::now());
+        assert!(wait.is_some_and(|wait| wait > Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,35 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::error::Error;
+
+/// The sample rate of `pcm16` audio in the Realtime API.
+pub const PCM16_SAMPLE_RATE: u32 = 24_000;
+
+/// Converts samples in `[-1.0, 1.0]` into 16-bit little-endian PCM bytes.
+pub fn f32_to_pcm16(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|sample| {
+            let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            sample.to_le_bytes()
+        })
+        .collect()
+}
+
+pub fn encode_pcm16(pcm: &[u8]) -> String {
+    STANDARD.encode(pcm)
+}
+
+/// Decodes the base64 audio of `response.audio.delta` into PCM bytes.
+pub fn decode_pcm16(audio: &str) -> Result<Vec<u8>, Error> {
+    STANDARD
+        .decode(audio)
+        .map_err(|e| Error::InvalidArgument(format!("Failed to decode audio. Error = {e}")))
+}
+
+/// Splits 24kHz 16-bit PCM bytes into base64 chunks of `chunk_ms` milliseconds for `input_audio_buffer.append`.
+pub fn pcm16_chunks(pcm: &[u8], chunk_ms: u32) -> impl Iterator<Item = String> + '_ {
+    let bytes_per_ms = (PCM16_SAMPLE_RATE / 1000 * 2) as usize;
+    let chunk_size = (bytes_per_ms * chunk_ms.max(1) as usize).max(2);
+    pcm.chunks(chunk_size).map(encode_pcm16)
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ChatAudioVoice, Modalities};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeAudioFormat {
+    /// 16-bit PCM at 24kHz, mono, little-endian.
+    Pcm16,
+    G711Ulaw,
+    G711Alaw,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeTool {
+    Function {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parameters: Option<serde_json::Value>,
+    },
+}
+
+/// The session configuration of `session.update`. Fields left `None` keep their current value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RealtimeSessionConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<Modalities>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<ChatAudioVoice>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_audio_format: Option<RealtimeAudioFormat>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_audio_format: Option<RealtimeAudioFormat>,
+
+    /// e.g. `{"model": "whisper-1"}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_audio_transcription: Option<serde_json::Value>,
+
+    /// e.g. `{"type": "server_vad"}`, or `null` to disable voice activity detection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_detection: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<RealtimeTool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// A number of tokens or `"inf"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_output_tokens: Option<serde_json::Value>,
+}
+
+/// The options of `response.create`. Fields left `None` use the session configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RealtimeResponseConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<Modalities>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<ChatAudioVoice>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<RealtimeTool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeContentPart {
+    InputText {
+        text: String,
+    },
+    InputAudio {
+        /// Base64 encoded audio.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        audio: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transcript: Option<String>,
+    },
+    Text {
+        text: String,
+    },
+    Audio {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        audio: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transcript: Option<String>,
+    },
+}
+
+/// An item of the conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum RealtimeItem {
+    Message {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        /// `user`, `assistant` or `system`.
+        role: String,
+        #[serde(default)]
+        content: Vec<RealtimeContentPart>,
+    },
+    FunctionCall {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        call_id: String,
+        name: String,
+        #[serde(default)]
+        arguments: String,
+    },
+    FunctionCallOutput {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        call_id: String,
+        output: String,
+    },
+}
+
+impl RealtimeItem {
+    pub fn user_text(text: impl Into<String>) -> Self {
+        Self::Message {
+            id: None,
+            role: "user".into(),
+            content: vec![RealtimeContentPart::InputText { text: text.into() }],
+        }
+    }
+
+    pub fn function_call_output(call_id: impl Into<String>, output: impl Into<String>) -> Self {
+        Self::FunctionCallOutput {
+            id: None,
+            call_id: call_id.into(),
+            output: output.into(),
+        }
+    }
+}
+
+/// An event sent to the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum RealtimeClientEvent {
+    #[serde(rename = "session.update")]
+    SessionUpdate { session: RealtimeSessionConfig },
+    /// Appends base64 encoded audio to the input buffer, see [`super::pcm16_chunks`].
+    #[serde(rename = "input_audio_buffer.append")]
+    InputAudioBufferAppend { audio: String },
+    #[serde(rename = "input_audio_buffer.commit")]
+    InputAudioBufferCommit,
+    #[serde(rename = "input_audio_buffer.clear")]
+    InputAudioBufferClear,
+    #[serde(rename = "conversation.item.create")]
+    ConversationItemCreate {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        previous_item_id: Option<String>,
+        item: RealtimeItem,
+    },
+    #[serde(rename = "conversation.item.delete")]
+    ConversationItemDelete { item_id: String },
+    #[serde(rename = "response.create")]
+    ResponseCreate {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        response: Option<RealtimeResponseConfig>,
+    },
+    #[serde(rename = "response.cancel")]
+    ResponseCancel,
+}
+
+impl RealtimeClientEvent {
+    pub fn item_create(item: RealtimeItem) -> Self {
+        Self::ConversationItemCreate {
+            previous_item_id: None,
+            item,
+        }
+    }
+
+    pub fn response_create() -> Self {
+        Self::ResponseCreate { response: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RealtimeError {
+    pub r#type: Option<String>,
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub param: Option<String>,
+    pub event_id: Option<String>,
+}
+
+/// An event received from the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum RealtimeServerEvent {
+    #[serde(rename = "error")]
+    Error { error: RealtimeError },
+    #[serde(rename = "session.created")]
+    SessionCreated { session: serde_json::Value },
+    #[serde(rename = "session.updated")]
+    SessionUpdated { session: serde_json::Value },
+    #[serde(rename = "conversation.item.created")]
+    ConversationItemCreated {
+        previous_item_id: Option<String>,
+        item: RealtimeItem,
+    },
+    #[serde(rename = "conversation.item.input_audio_transcription.completed")]
+    InputAudioTranscriptionCompleted { item_id: String, transcript: String },
+    #[serde(rename = "input_audio_buffer.committed")]
+    InputAudioBufferCommitted {
+        previous_item_id: Option<String>,
+        item_id: String,
+    },
+    #[serde(rename = "input_audio_buffer.speech_started")]
+    SpeechStarted {
+        audio_start_ms: u64,
+        item_id: String,
+    },
+    #[serde(rename = "input_audio_buffer.speech_stopped")]
+    SpeechStopped { audio_end_ms: u64, item_id: String },
+    #[serde(rename = "response.created")]
+    ResponseCreated { response: serde_json::Value },
+    /// The response is finished. `response.status` is `completed`, `cancelled`, `failed` or `incomplete`, and `response.usage` holds the token usage.
+    #[serde(rename = "response.done")]
+    ResponseDone { response: serde_json::Value },
+    #[serde(rename = "response.output_item.added")]
+    OutputItemAdded {
+        response_id: String,
+        output_index: u32,
+        item: RealtimeItem,
+    },
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone {
+        response_id: String,
+        output_index: u32,
+        item: RealtimeItem,
+    },
+    #[serde(rename = "response.text.delta")]
+    TextDelta {
+        response_id: String,
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.text.done")]
+    TextDone {
+        response_id: String,
+        item_id: String,
+        text: String,
+    },
+    /// Base64 encoded audio in the session's output format.
+    #[serde(rename = "response.audio.delta")]
+    AudioDelta {
+        response_id: String,
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.audio.done")]
+    AudioDone {
+        response_id: String,
+        item_id: String,
+    },
+    #[serde(rename = "response.audio_transcript.delta")]
+    AudioTranscriptDelta {
+        response_id: String,
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.audio_transcript.done")]
+    AudioTranscriptDone {
+        response_id: String,
+        item_id: String,
+        transcript: String,
+    },
+    #[serde(rename = "response.function_call_arguments.delta")]
+    FunctionCallArgumentsDelta {
+        response_id: String,
+        item_id: String,
+        call_id: String,
+        delta: String,
+    },
+    #[serde(rename = "response.function_call_arguments.done")]
+    FunctionCallArgumentsDone {
+        response_id: String,
+        item_id: String,
+        call_id: String,
+        name: Option<String>,
+        arguments: String,
+    },
+    #[serde(rename = "rate_limits.updated")]
+    RateLimitsUpdated { rate_limits: Vec<serde_json::Value> },
+    /// An event type unknown to this crate.
+    #[serde(other)]
+    Unknown,
+}
+
+impl RealtimeServerEvent {
+    /// The `(call_id, name, arguments)` of a finished function call. The name is only sent by some servers; it is always in the `function_call` item of `response.output_item.done`.
+    pub fn function_call(&self) -> Option<(&str, Option<&str>, &str)> {
+        match self {
+            RealtimeServerEvent::FunctionCallArgumentsDone {
+                call_id,
+                name,
+                arguments,
+                ..
+            } => Some((call_id, name.as_deref(), arguments)),
+            _ => None,
+        }
+    }
+
+    /// The text of `response.text.delta` and `response.audio_transcript.delta`.
+    pub fn text_delta(&self) -> Option<&str> {
+        match self {
+            RealtimeServerEvent::TextDelta { delta, .. }
+            | RealtimeServerEvent::AudioTranscriptDelta { delta, .. } => Some(delta),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, Stream, StreamExt,
+};
+use secrecy::ExposeSecret;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::{error::Error, providers::Config};
+
+pub mod audio;
+pub mod events;
+
+pub use audio::*;
+pub use events::*;
+
+fn ws_error(e: impl std::fmt::Display) -> Error {
+    Error::Stream(format!("WebSocket error. Error = {e}"))
+}
+
+/// A Realtime API session over a WebSocket. It yields the server events as a [`Stream`]; use [`RealtimeSession::split`] to send and receive from different tasks.
+#[derive(Debug)]
+pub struct RealtimeSession<S = MaybeTlsStream<TcpStream>> {
+    socket: WebSocketStream<S>,
+}
+
+impl RealtimeSession {
+    /// Opens a session with a model at the `/realtime` endpoint of the provider.
+    pub async fn connect(config: &impl Config, model: &str) -> Result<Self, Error> {
+        let base_url = config.base_url();
+        let url = match base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some(("http", rest)) => format!("ws://{rest}"),
+            _ => base_url.to_string(),
+        };
+        let mut request = format!("{url}/realtime?model={model}")
+            .into_client_request()
+            .map_err(ws_error)?;
+        let headers = request.headers_mut();
+        if let Some(api_key) = config.api_key() {
+            let bearer = format!("Bearer {}", api_key.expose_secret());
+            headers.insert(
+                "Authorization",
+                bearer.parse().map_err(|e| {
+                    Error::InvalidConfig(format!(
+                        "Failed to convert api key id to header value. {:?}",
+                        e
+                    ))
+                })?,
+            );
+        }
+        headers.insert("OpenAI-Beta", "realtime=v1".parse().expect("valid header"));
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(ws_error)?;
+        Ok(Self { socket })
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> RealtimeSession<S> {
+    /// Wraps an established WebSocket, e.g. to a local stand-in server.
+    pub fn from_socket(socket: WebSocketStream<S>) -> Self {
+        Self { socket }
+    }
+
+    pub async fn send(&mut self, event: &RealtimeClientEvent) -> Result<(), Error> {
+        send(&mut self.socket, event).await
+    }
+
+    pub async fn close(mut self) -> Result<(), Error> {
+        self.socket.close(None).await.map_err(ws_error)
+    }
+
+    /// Splits the session into a sender and a receiver.
+    pub fn split(self) -> (RealtimeSender<S>, RealtimeReceiver<S>) {
+        let (sink, stream) = self.socket.split();
+        (RealtimeSender { sink }, RealtimeReceiver { stream })
+    }
+}
+
+async fn send<T>(sink: &mut T, event: &RealtimeClientEvent) -> Result<(), Error>
+where
+    T: futures::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    let text = serde_json::to_string(event)?;
+    sink.send(Message::Text(text.into()))
+        .await
+        .map_err(ws_error)
+}
+
+/// Parses a WebSocket message into a server event. Control frames yield `None`.
+fn parse(
+    message: Result<Message, tokio_tungstenite::tungstenite::Error>,
+) -> Option<Result<RealtimeServerEvent, Error>> {
+    match message {
+        Ok(Message::Text(text)) => Some(serde_json::from_str(&text).map_err(Error::from)),
+        Ok(Message::Binary(_)) => Some(Err(Error::Stream(
+            "Unexpected binary WebSocket message".into(),
+        ))),
+        Ok(_) => None,
+        Err(e) => Some(Err(ws_error(e))),
+    }
+}
+
+fn poll_events<T>(
+    stream: &mut T,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Result<RealtimeServerEvent, Error>>>
+where
+    T: Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        match stream.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(Message::Close(_)))) | Poll::Ready(None) => {
+                return Poll::Ready(None)
+            }
+            Poll::Ready(Some(message)) => {
+                if let Some(event) = parse(message) {
+                    return Poll::Ready(Some(event));
+                }
+            }
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Stream for RealtimeSession<S> {
+    type Item = Result<RealtimeServerEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_events(&mut self.socket, cx)
+    }
+}
+
+/// The sending half of a [`RealtimeSession`].
+#[derive(Debug)]
+pub struct RealtimeSender<S = MaybeTlsStream<TcpStream>> {
+    sink: SplitSink<WebSocketStream<S>, Message>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> RealtimeSender<S> {
+    pub async fn send(&mut self, event: &RealtimeClientEvent) -> Result<(), Error> {
+        send(&mut self.sink, event).await
+    }
+
+    pub async fn update_session(&mut self, session: RealtimeSessionConfig) -> Result<(), Error> {
+        self.send(&RealtimeClientEvent::SessionUpdate { session })
+            .await
+    }
+
+    /// Adds a user text message and asks for a response.
+    pub async fn send_text(&mut self, text: impl Into<String>) -> Result<(), Error> {
+        self.send(&RealtimeClientEvent::item_create(RealtimeItem::user_text(
+            text,
+        )))
+        .await?;
+        self.send(&RealtimeClientEvent::response_create()).await
+    }
+
+    /// Appends 24kHz 16-bit PCM audio to the input buffer in chunks of `chunk_ms` milliseconds.
+    pub async fn append_audio(&mut self, pcm: &[u8], chunk_ms: u32) -> Result<(), Error> {
+        for audio in pcm16_chunks(pcm, chunk_ms) {
+            self.send(&RealtimeClientEvent::InputAudioBufferAppend { audio })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the result of a function call to the model and asks for a response.
+    pub async fn send_function_output(
+        &mut self,
+        call_id: impl Into<String>,
+        output: impl Into<String>,
+    ) -> Result<(), Error> {
+        self.send(&RealtimeClientEvent::item_create(
+            RealtimeItem::function_call_output(call_id, output),
+        ))
+        .await?;
+        self.send(&RealtimeClientEvent::response_create()).await
+    }
+
+    pub async fn close(&mut self) -> Result<(), Error> {
+        self.sink.close().await.map_err(ws_error)
+    }
+}
+
+/// The receiving half of a [`RealtimeSession`], a stream of server events.
+#[derive(Debug)]
+pub struct RealtimeReceiver<S = MaybeTlsStream<TcpStream>> {
+    stream: SplitStream<WebSocketStream<S>>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Stream for RealtimeReceiver<S> {
+    type Item = Result<RealtimeServerEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_events(&mut self.stream, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// A local stand-in that waits for `response.create` and replays recorded server events.
+    async fn replay(events: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                if message.to_text().unwrap().contains("response.create") {
+                    break;
+                }
+            }
+            for event in events {
+                socket.send(Message::Text(event.into())).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+        });
+        format!("ws://{address}")
+    }
+
+    #[tokio::test]
+    async fn realtime_session_works() {
+        let url = replay(vec![
+            r#"{"type":"session.created","event_id":"event_1","session":{"id":"sess_1"}}"#,
+            r#"{"type":"response.text.delta","event_id":"event_2","response_id":"resp_1","item_id":"item_1","output_index":0,"content_index":0,"delta":"Hello"}"#,
+            r#"{"type":"response.function_call_arguments.done","event_id":"event_3","response_id":"resp_1","item_id":"item_2","output_index":1,"call_id":"call_1","name":"get_weather","arguments":"{}"}"#,
+            r#"{"type":"response.content_part.added","event_id":"event_4"}"#,
+        ])
+        .await;
+        let (socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        let (mut sender, receiver) = RealtimeSession::from_socket(socket).split();
+        sender.send_text("Hi").await.unwrap();
+
+        let events: Vec<_> = receiver.map(Result::unwrap).collect().await;
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[1].text_delta(), Some("Hello"));
+        assert_eq!(
+            events[2].function_call(),
+            Some(("call_1", Some("get_weather"), "{}"))
+        );
+        assert_eq!(events[3], RealtimeServerEvent::Unknown);
+    }
+
+    #[test]
+    fn pcm16_chunks_works() {
+        let pcm = f32_to_pcm16(&vec![0.5; 2400]);
+        assert_eq!(pcm.len(), 4800);
+        let chunks: Vec<_> = pcm16_chunks(&pcm, 40).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(decode_pcm16(&chunks[0]).unwrap().len(), 1920);
+    }
+}
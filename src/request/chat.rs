@@ -4,7 +4,9 @@ use futures::Stream;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    context::TokenCounter,
     error::Error,
+    moderations::ModerationInput,
     types::{
         AssistantContent, ChatAudio, ChatFunction, ChatFunctionCall, ChatResponseFormat, ChatTool,
         ChatToolChoice, Content, Modalities, PredictionContent, ReasoningEffort, ServiceTier, Stop,
@@ -278,6 +280,45 @@ impl Requestable for ChatRequest {
     fn stream(&self) -> bool {
         self.stream.unwrap_or(false)
     }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    #[allow(deprecated)]
+    fn estimate_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        let completion = self.max_completion_tokens.or(self.max_tokens).unwrap_or(0);
+        counter.count_request(self) + completion as usize
+    }
+
+    fn moderation_input(&self) -> Option<ModerationInput> {
+        self.messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                ChatMessage::User { content, .. } => Some(content.into()),
+                _ => None,
+            })
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    #[allow(deprecated)]
+    fn max_tokens(&self) -> Option<u32> {
+        self.max_completion_tokens.or(self.max_tokens)
+    }
+
+    fn prompt(&self) -> Option<String> {
+        serde_json::to_string(&self.messages).ok()
+    }
+
+    fn include_usage(&mut self) {
+        self.stream_options
+            .get_or_insert_with(StreamOptions::default)
+            .include_usage = Some(true);
+    }
 }
 
 impl Printable for ChatRequest {
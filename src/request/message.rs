@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    AssistantAudio, AssistantContent, AssistantFunctionCall, AssistantToolCall, Content, ImageUrl,
-    UserContent, UserContentPart,
+    AssistantAudio, AssistantContent, AssistantFunctionCall, AssistantToolCall, ChatChoiceMessage,
+    ChatMessageFunctionCall, ChatMessageToolCall, Content, ImageUrl, ToolType, UserContent,
+    UserContentPart,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -146,6 +147,64 @@ impl ChatMessage {
     }
 }
 
+impl From<ChatMessageFunctionCall> for AssistantFunctionCall {
+    fn from(value: ChatMessageFunctionCall) -> Self {
+        Self {
+            name: value.name.unwrap_or_default(),
+            arguments: value.arguments.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ChatMessageToolCall> for AssistantToolCall {
+    fn from(value: ChatMessageToolCall) -> Self {
+        Self {
+            id: value.id.unwrap_or_default(),
+            r#type: ToolType::Function,
+            function: value.function.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+/// Converts a message generated by the model into an assistant message, so it can be appended to the conversation history.
+impl From<ChatChoiceMessage> for ChatMessage {
+    #[allow(deprecated)]
+    fn from(value: ChatChoiceMessage) -> Self {
+        Self::Assistant {
+            content: value.content.map(AssistantContent::Text),
+            refusal: value.refusal,
+            name: None,
+            audio: value
+                .audio
+                .and_then(|audio| audio.id)
+                .map(|id| AssistantAudio { id }),
+            tool_calls: value
+                .tool_calls
+                .filter(|tool_calls| !tool_calls.is_empty())
+                .map(|tool_calls| tool_calls.into_iter().map(Into::into).collect()),
+            function_call: value.function_call.map(Into::into),
+        }
+    }
+}
+
+impl ChatMessage {
+    pub fn is_system(&self) -> bool {
+        matches!(self, Self::System { .. } | Self::Developer { .. })
+    }
+
+    pub fn is_user(&self) -> bool {
+        matches!(self, Self::User { .. })
+    }
+
+    pub fn is_assistant(&self) -> bool {
+        matches!(self, Self::Assistant { .. })
+    }
+
+    pub fn is_tool(&self) -> bool {
+        matches!(self, Self::Tool { .. })
+    }
+}
+
 // impl TryInto<ChatCompletionRequestMessage> for ChatMessage {
 //     type Error = Error;
 //     fn try_into(self) -> Result<ChatCompletionRequestMessage, Self::Error> {
@@ -1,3 +1,5 @@
+use crate::{context::TokenCounter, moderations::ModerationInput, types::UserContent};
+
 pub mod chat;
 pub mod message;
 
@@ -6,6 +8,38 @@ pub use message::ChatMessage;
 
 pub trait Requestable {
     fn stream(&self) -> bool;
+
+    fn model(&self) -> Option<&str> {
+        None
+    }
+
+    /// Estimates the tokens the request may consume: the prompt plus the maximum completion length.
+    fn estimate_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        let _ = counter;
+        0
+    }
+
+    /// The content screened by a [`crate::moderations::ModerationGuard`], usually the last user message.
+    fn moderation_input(&self) -> Option<ModerationInput> {
+        None
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        None
+    }
+
+    /// The maximum number of tokens to generate.
+    fn max_tokens(&self) -> Option<u32> {
+        None
+    }
+
+    /// The prompt as JSON, e.g. the messages of a chat request, recorded when telemetry captures content.
+    fn prompt(&self) -> Option<String> {
+        None
+    }
+
+    /// Asks for the usage in the last chunk of a stream, so that it can be tracked and budgeted. The default implementation does nothing.
+    fn include_usage(&mut self) {}
 }
 
 impl Requestable for serde_json::Value {
@@ -15,4 +49,59 @@ impl Requestable for serde_json::Value {
             _ => false,
         }
     }
+
+    fn model(&self) -> Option<&str> {
+        self.get("model").and_then(|model| model.as_str())
+    }
+
+    fn estimate_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        let prompt = self
+            .get("messages")
+            .map(|messages| counter.count_text(&messages.to_string()))
+            .unwrap_or(0);
+        let completion = ["max_completion_tokens", "max_tokens"]
+            .iter()
+            .find_map(|key| self.get(*key).and_then(|value| value.as_u64()))
+            .unwrap_or(0);
+        prompt + completion as usize
+    }
+
+    fn moderation_input(&self) -> Option<ModerationInput> {
+        let message = self
+            .get("messages")?
+            .as_array()?
+            .iter()
+            .rev()
+            .find(|message| message.get("role").and_then(|role| role.as_str()) == Some("user"))?;
+        let content: UserContent = serde_json::from_value(message.get("content")?.clone()).ok()?;
+        Some((&content).into())
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        self.get("temperature")?.as_f64().map(|t| t as f32)
+    }
+
+    fn include_usage(&mut self) {
+        if let Some(request) = self.as_object_mut() {
+            let options = request
+                .entry("stream_options")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(options) = options.as_object_mut() {
+                options.insert("include_usage".into(), true.into());
+            }
+        }
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        ["max_completion_tokens", "max_tokens"]
+            .iter()
+            .find_map(|key| self.get(*key)?.as_u64())
+            .map(|tokens| tokens.try_into().unwrap_or(u32::MAX))
+    }
+
+    fn prompt(&self) -> Option<String> {
+        self.get("messages")
+            .or_else(|| self.get("prompt"))
+            .map(|prompt| prompt.to_string())
+    }
 }
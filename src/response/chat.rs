@@ -1,7 +1,11 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    types::{ChatChoice, ChatChoiceStream, CompletionUsage, CompletionUsageStream},
+    json::{JsonRepair, RepairReport},
+    types::{
+        ChatChoice, ChatChoiceMessage, ChatChoiceStream, ChatMessageFunctionCall,
+        ChatMessageToolCall, CompletionUsage, CompletionUsageStream,
+    },
     Error, Printable,
 };
 
@@ -65,10 +69,202 @@ pub struct ChatResponseStream {
     pub usage: Option<CompletionUsageStream>,
 }
 
+impl ChatResponse {
+    /// Builds a complete response by merging the chunks of a streamed response in order.
+    pub fn from_stream(chunks: impl IntoIterator<Item = ChatResponseStream>) -> Self {
+        let mut response = Self {
+            id: None,
+            choices: vec![],
+            created: None,
+            model: None,
+            service_tier: None,
+            system_fingerprint: None,
+            object: Some("chat.completion".into()),
+            usage: None,
+        };
+        for chunk in chunks {
+            response.merge_stream(chunk);
+        }
+        response
+    }
+
+    /// Parses the message content of the first choice as JSON, e.g. structured output, repairing common mistakes with [`JsonRepair`].
+    pub fn parse_json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        self.parse_json_with(&JsonRepair::new())
+            .map(|(value, _)| value)
+    }
+
+    pub fn parse_json_with<T: DeserializeOwned>(
+        &self,
+        repair: &JsonRepair,
+    ) -> Result<(T, RepairReport), Error> {
+        let content = self
+            .text()
+            .ok_or_else(|| Error::InvalidArgument("The response has no content".into()))?;
+        repair.parse(content)
+    }
+
+    /// Merges a streamed chunk into this response. Content and refusal deltas are appended, tool call deltas are matched by `index` (or `id`) and their arguments concatenated.
+    pub fn merge_stream(&mut self, chunk: ChatResponseStream) {
+        if chunk.id.is_some() {
+            self.id = chunk.id;
+        }
+        if chunk.created.is_some() {
+            self.created = chunk.created;
+        }
+        if chunk.model.is_some() {
+            self.model = chunk.model;
+        }
+        if chunk.service_tier.is_some() {
+            self.service_tier = chunk.service_tier;
+        }
+        if chunk.system_fingerprint.is_some() {
+            self.system_fingerprint = chunk.system_fingerprint;
+        }
+        if let Some(usage) = chunk.usage {
+            self.usage = Some(usage.into());
+        }
+        for choice in chunk.choices {
+            self.merge_choice(choice);
+        }
+    }
+
+    fn merge_choice(&mut self, delta: ChatChoiceStream) {
+        let index = delta.index.unwrap_or(0);
+        let position = match self
+            .choices
+            .iter()
+            .position(|choice| choice.index.unwrap_or(0) == index)
+        {
+            Some(position) => position,
+            None => {
+                self.choices.push(ChatChoice {
+                    finish_reason: None,
+                    index: Some(index),
+                    message: Some(ChatChoiceMessage::default()),
+                    logprobs: None,
+                });
+                self.choices.len() - 1
+            }
+        };
+        let choice = &mut self.choices[position];
+
+        if delta.finish_reason.is_some() {
+            choice.finish_reason = delta.finish_reason;
+        }
+
+        if let Some(logprobs) = delta.logprobs {
+            let target = choice.logprobs.get_or_insert_with(Default::default);
+            if let Some(content) = logprobs.content {
+                target.content.get_or_insert_with(Vec::new).extend(content);
+            }
+            if let Some(refusal) = logprobs.refusal {
+                target.refusal.get_or_insert_with(Vec::new).extend(refusal);
+            }
+        }
+
+        let Some(delta) = delta.delta else {
+            return;
+        };
+        let message = choice.message.get_or_insert_with(Default::default);
+        if delta.role.is_some() {
+            message.role = delta.role;
+        }
+        if let Some(content) = delta.content {
+            message
+                .content
+                .get_or_insert_with(String::new)
+                .push_str(&content);
+        }
+        if let Some(refusal) = delta.refusal {
+            message
+                .refusal
+                .get_or_insert_with(String::new)
+                .push_str(&refusal);
+        }
+        if let Some(function_call) = delta.function_call {
+            merge_function_call(
+                message.function_call.get_or_insert_with(Default::default),
+                function_call,
+            );
+        }
+        for tool_call in delta.tool_calls.into_iter().flatten() {
+            merge_tool_call(message.tool_calls.get_or_insert_with(Vec::new), tool_call);
+        }
+    }
+}
+
+fn merge_function_call(target: &mut ChatMessageFunctionCall, delta: ChatMessageFunctionCall) {
+    if let Some(name) = delta.name {
+        target.name.get_or_insert_with(String::new).push_str(&name);
+    }
+    if let Some(arguments) = delta.arguments {
+        target
+            .arguments
+            .get_or_insert_with(String::new)
+            .push_str(&arguments);
+    }
+}
+
+fn merge_tool_call(tool_calls: &mut Vec<ChatMessageToolCall>, delta: ChatMessageToolCall) {
+    // Providers that omit `index` send the id only on the first delta of each call.
+    let position = match (delta.index, &delta.id) {
+        (Some(index), _) => tool_calls.iter().position(|t| t.index == Some(index)),
+        (None, Some(id)) => tool_calls.iter().position(|t| t.id.as_ref() == Some(id)),
+        (None, None) => tool_calls.len().checked_sub(1),
+    };
+    let Some(position) = position else {
+        tool_calls.push(delta);
+        return;
+    };
+    let target = &mut tool_calls[position];
+    if delta.id.is_some() {
+        target.id = delta.id;
+    }
+    if delta.r#type.is_some() {
+        target.r#type = delta.r#type;
+    }
+    if let Some(function) = delta.function {
+        merge_function_call(
+            target.function.get_or_insert_with(Default::default),
+            function,
+        );
+    }
+}
+
+impl From<ChatResponseStream> for ChatResponse {
+    fn from(value: ChatResponseStream) -> Self {
+        Self::from_stream([value])
+    }
+}
+
 impl Respondable for ChatResponse {
     fn is_success(&self) -> bool {
         true
     }
+
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.usage.clone()
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.choices.first()?.message.as_ref()?.content.as_deref()
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn finish_reasons(&self) -> Vec<&str> {
+        self.choices
+            .iter()
+            .filter_map(|choice| choice.finish_reason.as_deref())
+            .collect()
+    }
 }
 
 impl Printable for ChatResponse {
@@ -81,6 +277,29 @@ impl Respondable for ChatResponseStream {
     fn is_success(&self) -> bool {
         true
     }
+
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.usage.clone().map(Into::into)
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.choices.first()?.delta.as_ref()?.content.as_deref()
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn finish_reasons(&self) -> Vec<&str> {
+        self.choices
+            .iter()
+            .filter_map(|choice| choice.finish_reason.as_deref())
+            .collect()
+    }
 }
 
 impl Printable for ChatResponseStream {
@@ -2,15 +2,69 @@ pub mod chat;
 
 pub use chat::{ChatResponse, ChatResponseStream};
 
-use crate::{Error, Printable};
+use crate::{types::CompletionUsage, Error, Printable};
 
 pub trait Respondable {
     fn is_success(&self) -> bool {
         true
     }
+
+    /// The model that generated the response, if reported.
+    fn model(&self) -> Option<&str> {
+        None
+    }
+
+    /// The token usage of the response, if reported. For streams, only the last chunk carries usage when `stream_options.include_usage` is set.
+    fn usage(&self) -> Option<CompletionUsage> {
+        None
+    }
+
+    /// The message content of the first choice. For streams, the content delta of the chunk.
+    fn text(&self) -> Option<&str> {
+        None
+    }
+
+    /// The unique identifier of the response, if reported.
+    fn id(&self) -> Option<&str> {
+        None
+    }
+
+    /// The reasons the choices stopped, e.g. `stop` or `length`. For streams, only the last chunk of each choice has one.
+    fn finish_reasons(&self) -> Vec<&str> {
+        vec![]
+    }
 }
 
-impl Respondable for serde_json::Value {}
+impl Respondable for serde_json::Value {
+    fn model(&self) -> Option<&str> {
+        self.get("model").and_then(|model| model.as_str())
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.get("usage")
+            .filter(|usage| !usage.is_null())
+            .and_then(|usage| serde_json::from_value(usage.clone()).ok())
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.pointer("/choices/0/message/content")
+            .or_else(|| self.pointer("/choices/0/delta/content"))
+            .and_then(|content| content.as_str())
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.get("id").and_then(|id| id.as_str())
+    }
+
+    fn finish_reasons(&self) -> Vec<&str> {
+        self.get("choices")
+            .and_then(|choices| choices.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|choice| choice.get("finish_reason")?.as_str())
+            .collect()
+    }
+}
 
 impl Printable for serde_json::Value {
     fn to_string_pretty(&self) -> Result<String, Error> {
@@ -0,0 +1,202 @@
+use crate::{
+    types::{
+        AssistantContent, AssistantContentPart, AssistantFunctionCall, AssistantToolCall, ChatTool,
+        ChatToolChoice, ChatToolChoiceNamedOption, Content, ToolType, UserContent, UserContentPart,
+    },
+    ChatMessage, ChatRequest,
+};
+
+use super::{
+    Response, ResponseContentPart, ResponseInput, ResponseInputItem, ResponseMessageContent,
+    ResponseOutputItem, ResponseReasoning, ResponseRequest, ResponseRole, ResponseTool,
+};
+
+fn content_text(content: &Content) -> String {
+    match content {
+        Content::Text(text) => text.clone(),
+        Content::Array(texts) => texts.join("\n"),
+    }
+}
+
+impl ResponseInputItem {
+    /// Converts chat messages into input items. Assistant tool calls become separate `function_call` items and tool messages `function_call_output` items. Audio parts are not supported and skipped.
+    pub fn from_messages<'a>(messages: impl IntoIterator<Item = &'a ChatMessage>) -> Vec<Self> {
+        let mut items = vec![];
+        for message in messages {
+            match message {
+                ChatMessage::Developer { content, .. } => items.push(Self::message(
+                    ResponseRole::Developer,
+                    content_text(content),
+                )),
+                ChatMessage::System { content, .. } => {
+                    items.push(Self::message(ResponseRole::System, content_text(content)))
+                }
+                ChatMessage::User { content, .. } => {
+                    let content = match content {
+                        UserContent::Text(text) => ResponseMessageContent::Text(text.clone()),
+                        UserContent::Array(parts) => ResponseMessageContent::Parts(
+                            parts
+                                .iter()
+                                .filter_map(|part| match part {
+                                    UserContentPart::Text { text } => {
+                                        Some(ResponseContentPart::InputText { text: text.clone() })
+                                    }
+                                    UserContentPart::ImageUrl { image_url } => {
+                                        Some(ResponseContentPart::InputImage {
+                                            image_url: Some(image_url.url.clone()),
+                                            file_id: None,
+                                            detail: image_url.detail.clone(),
+                                        })
+                                    }
+                                    UserContentPart::Audio { .. } => None,
+                                })
+                                .collect(),
+                        ),
+                    };
+                    items.push(Self::message(ResponseRole::User, content));
+                }
+                #[allow(deprecated)]
+                ChatMessage::Assistant {
+                    content,
+                    refusal,
+                    tool_calls,
+                    function_call,
+                    ..
+                } => {
+                    let text = match content {
+                        Some(AssistantContent::Text(text)) => Some(text.clone()),
+                        Some(AssistantContent::Array(parts)) => Some(
+                            parts
+                                .iter()
+                                .filter_map(|part| match part {
+                                    AssistantContentPart::Text(text) => Some(text.as_str()),
+                                    AssistantContentPart::Refusal { .. } => None,
+                                })
+                                .collect(),
+                        ),
+                        None => refusal.clone(),
+                    };
+                    if let Some(text) = text.filter(|text| !text.is_empty()) {
+                        items.push(Self::message(ResponseRole::Assistant, text));
+                    }
+                    for tool_call in tool_calls.iter().flatten() {
+                        items.push(Self::FunctionCall {
+                            call_id: tool_call.id.clone(),
+                            name: tool_call.function.name.clone(),
+                            arguments: tool_call.function.arguments.clone(),
+                        });
+                    }
+                    if let Some(function_call) = function_call {
+                        items.push(Self::FunctionCall {
+                            call_id: function_call.name.clone(),
+                            name: function_call.name.clone(),
+                            arguments: function_call.arguments.clone(),
+                        });
+                    }
+                }
+                ChatMessage::Tool {
+                    content,
+                    tool_call_id,
+                } => items.push(Self::function_call_output(
+                    tool_call_id.clone(),
+                    content_text(content),
+                )),
+            }
+        }
+        items
+    }
+}
+
+impl From<ChatTool> for ResponseTool {
+    fn from(value: ChatTool) -> Self {
+        match value {
+            ChatTool::Function { function } => ResponseTool::Function {
+                name: function.name,
+                description: function.description,
+                parameters: function.parameters,
+                strict: function.strict,
+            },
+        }
+    }
+}
+
+/// Converts a chat request into a Responses request with the same messages and the parameters both APIs support.
+impl From<&ChatRequest> for ResponseRequest {
+    #[allow(deprecated)]
+    fn from(value: &ChatRequest) -> Self {
+        let tool_choice = value
+            .tool_choice
+            .as_ref()
+            .map(|tool_choice| match tool_choice {
+                ChatToolChoice::Function(ChatToolChoiceNamedOption::Function { function }) => {
+                    serde_json::json!({"type": "function", "name": function.name})
+                }
+                tool_choice => serde_json::to_value(tool_choice).unwrap_or_default(),
+            });
+        Self {
+            model: value.model.clone(),
+            input: ResponseInput::Items(ResponseInputItem::from_messages(&value.messages)),
+            tools: value
+                .tools
+                .clone()
+                .map(|tools| tools.into_iter().map(Into::into).collect()),
+            tool_choice,
+            parallel_tool_calls: value.parallel_tool_calls,
+            temperature: value.temperature,
+            top_p: value.top_p,
+            max_output_tokens: value.max_completion_tokens.or(value.max_tokens),
+            reasoning: value
+                .reasoning_effort
+                .clone()
+                .map(|effort| ResponseReasoning {
+                    effort: Some(effort),
+                    summary: None,
+                }),
+            store: value.store,
+            stream: value.stream,
+            metadata: value
+                .metadata
+                .clone()
+                .and_then(|metadata| serde_json::from_value(metadata).ok()),
+            user: value.user.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts the output of a response into one assistant message, so it can be appended to a chat history.
+impl From<&Response> for ChatMessage {
+    #[allow(deprecated)]
+    fn from(value: &Response) -> Self {
+        let mut refusal = None;
+        for item in &value.output {
+            if let ResponseOutputItem::Message { content, .. } = item {
+                for part in content {
+                    if let ResponseContentPart::Refusal { refusal: text } = part {
+                        refusal = Some(text.clone());
+                    }
+                }
+            }
+        }
+        let text = value.output_text();
+        let tool_calls: Vec<AssistantToolCall> = value
+            .function_calls()
+            .map(|(call_id, name, arguments)| AssistantToolCall {
+                id: call_id.into(),
+                r#type: ToolType::Function,
+                function: AssistantFunctionCall {
+                    name: name.into(),
+                    arguments: arguments.into(),
+                },
+            })
+            .collect();
+        ChatMessage::Assistant {
+            content: (!text.is_empty()).then_some(AssistantContent::Text(text)),
+            refusal,
+            name: None,
+            audio: None,
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            function_call: None,
+        }
+    }
+}
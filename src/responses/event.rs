@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{response::Respondable, types::CompletionUsage};
+
+use super::{Response, ResponseContentPart, ResponseOutputItem};
+
+/// A server-sent event of a streamed response. An `error` event is not an event but fails the stream with [`crate::Error::Api`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum ResponseStreamEvent {
+    #[serde(rename = "response.created")]
+    Created { response: Response },
+    #[serde(rename = "response.in_progress")]
+    InProgress { response: Response },
+    #[serde(rename = "response.completed")]
+    Completed { response: Response },
+    #[serde(rename = "response.incomplete")]
+    Incomplete { response: Response },
+    #[serde(rename = "response.failed")]
+    Failed { response: Response },
+    #[serde(rename = "response.output_item.added")]
+    OutputItemAdded {
+        output_index: u32,
+        item: ResponseOutputItem,
+    },
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone {
+        output_index: u32,
+        item: ResponseOutputItem,
+    },
+    #[serde(rename = "response.content_part.added")]
+    ContentPartAdded {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        part: ResponseContentPart,
+    },
+    #[serde(rename = "response.content_part.done")]
+    ContentPartDone {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        part: ResponseContentPart,
+    },
+    #[serde(rename = "response.output_text.delta")]
+    OutputTextDelta {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        delta: String,
+    },
+    #[serde(rename = "response.output_text.done")]
+    OutputTextDone {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        text: String,
+    },
+    #[serde(rename = "response.refusal.delta")]
+    RefusalDelta {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        delta: String,
+    },
+    #[serde(rename = "response.refusal.done")]
+    RefusalDone {
+        item_id: String,
+        output_index: u32,
+        content_index: u32,
+        refusal: String,
+    },
+    #[serde(rename = "response.function_call_arguments.delta")]
+    FunctionCallArgumentsDelta {
+        item_id: String,
+        output_index: u32,
+        delta: String,
+    },
+    #[serde(rename = "response.function_call_arguments.done")]
+    FunctionCallArgumentsDone {
+        item_id: String,
+        output_index: u32,
+        arguments: String,
+    },
+    /// An event type unknown to this crate, e.g. of a built-in tool.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ResponseStreamEvent {
+    /// The final response of `response.completed`, `response.incomplete` and `response.failed`.
+    pub fn final_response(&self) -> Option<&Response> {
+        match self {
+            ResponseStreamEvent::Completed { response }
+            | ResponseStreamEvent::Incomplete { response }
+            | ResponseStreamEvent::Failed { response } => Some(response),
+            _ => None,
+        }
+    }
+
+    /// Whether no events follow.
+    pub fn is_terminal(&self) -> bool {
+        self.final_response().is_some()
+    }
+
+    /// The text of `response.output_text.delta`.
+    pub fn text_delta(&self) -> Option<&str> {
+        match self {
+            ResponseStreamEvent::OutputTextDelta { delta, .. } => Some(delta),
+            _ => None,
+        }
+    }
+}
+
+impl Respondable for ResponseStreamEvent {
+    fn is_success(&self) -> bool {
+        !matches!(self, ResponseStreamEvent::Failed { .. })
+    }
+
+    fn model(&self) -> Option<&str> {
+        self.final_response()?.model.as_deref()
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.final_response()?.usage()
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.text_delta()
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.final_response().map(|response| response.id.as_str())
+    }
+}
@@ -0,0 +1,344 @@
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt};
+
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+use crate::{
+    error::Error,
+    http::{
+        stream::{cancellable, with_cancellation},
+        HttpClient,
+    },
+    moderations::{ModerationInput, ModerationStage},
+    request::Requestable,
+    response::Respondable,
+    Client, Provider,
+};
+
+pub mod convert;
+pub mod event;
+pub mod request;
+pub mod response;
+
+pub use event::*;
+pub use request::*;
+pub use response::*;
+
+/// The Responses API (`/responses`), with stored conversation state and built-in tools.
+#[derive(Debug, Clone)]
+pub struct Responses<'c, P: Provider, H: HttpClient> {
+    pub(crate) client: &'c Client<P, H>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) cancellation: Option<CancellationToken>,
+}
+
+impl<'c, P: Provider, H: HttpClient> Responses<'c, P, H> {
+    pub fn new(client: &'c Client<P, H>) -> Self {
+        Self {
+            client,
+            tags: vec![],
+            cancellation: None,
+        }
+    }
+
+    /// Adds a caller-defined tag under which the usage of the following calls is aggregated by the client's usage tracker.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Cancels the following calls when the token is cancelled: pending calls fail and streams end with [`Error::Cancelled`], closing the connection.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Creates a response. Like chat calls, it is screened by the client's moderation guard and recorded by its telemetry.
+    pub async fn create(&self, request: ResponseRequest) -> Result<Response, Error> {
+        if request.stream() {
+            return Err(Error::InvalidArgument(
+                "When stream is true, use the responses.create_stream function instead".into(),
+            ));
+        }
+        let instrumentation = self.client.instrument("chat", &request);
+        let result = self
+            .send(request)
+            .instrument(instrumentation.span().clone())
+            .await;
+        instrumentation.finish(result.as_ref());
+        result
+    }
+
+    /// Streams the events of a response. The stream ends after the `response.completed`, `response.incomplete` or `response.failed` event, and fails with [`Error::Api`] on an `error` event.
+    pub async fn create_stream(
+        &self,
+        mut request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<ResponseStreamEvent, Error>> + Send>>, Error> {
+        request.stream = Some(true);
+        let instrumentation = self.client.instrument("chat", &request);
+        let result = self
+            .open_stream(request)
+            .instrument(instrumentation.span().clone())
+            .await;
+        match result {
+            Ok(stream) => Ok(instrumentation.stream(stream)),
+            Err(e) => {
+                instrumentation.fail(&e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn send(&self, request: ResponseRequest) -> Result<Response, Error> {
+        let recorder = self.client.usage_recorder(&self.tags);
+        recorder.check(Some(&request.model))?;
+        if self.client.moderates(ModerationStage::Input) {
+            let input = request.moderation_input();
+            self.client.moderate(ModerationStage::Input, input).await?;
+        }
+        self.client.acquire(&request).await;
+        let model = request.model.clone();
+        let response: Response = with_cancellation(
+            self.client.http_client.post("/responses", request),
+            self.cancellation.as_ref(),
+        )
+        .await?;
+        if let Some(usage) = response.usage() {
+            recorder.record(response.model.as_deref().unwrap_or(&model), &usage);
+        }
+        if self.client.moderates(ModerationStage::Output) {
+            let output = ModerationInput::from(response.output_text());
+            self.client
+                .moderate(ModerationStage::Output, Some(output))
+                .await?;
+        }
+        Ok(response)
+    }
+
+    async fn open_stream(
+        &self,
+        request: ResponseRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<ResponseStreamEvent, Error>> + Send>>, Error> {
+        let recorder = self.client.usage_recorder(&self.tags);
+        recorder.check(Some(&request.model))?;
+        if self.client.moderates(ModerationStage::Input) {
+            let input = request.moderation_input();
+            self.client.moderate(ModerationStage::Input, input).await?;
+        }
+        self.client.acquire(&request).await;
+        let model = request.model.clone();
+        let mut stream = with_cancellation(
+            self.client
+                .http_client
+                .post_stream::<_, ResponseStreamEvent>("/responses", request),
+            self.cancellation.as_ref(),
+        )
+        .await?;
+        if let Some(cancellation) = &self.cancellation {
+            stream = cancellable(stream, cancellation.clone());
+        }
+        // The API closes the connection after the last event instead of sending `[DONE]`.
+        let stream = stream.scan(false, |done, event| {
+            if *done {
+                return futures::future::ready(None);
+            }
+            *done = event.as_ref().is_ok_and(ResponseStreamEvent::is_terminal);
+            futures::future::ready(Some(event))
+        });
+        let mut stream: Pin<Box<dyn Stream<Item = _> + Send>> =
+            Box::pin(stream.inspect(move |event| {
+                if let Ok(event) = event {
+                    if let Some(usage) = event.usage() {
+                        recorder.record(event.model().unwrap_or(&model), &usage);
+                    }
+                }
+            }));
+        if let Some(guard) = &self.client.moderation_guard {
+            if guard.checks(ModerationStage::Output) {
+                let http_client = self.client.http_client.clone();
+                stream = guard.clone().screen_stream(http_client, stream);
+            }
+        }
+        Ok(stream)
+    }
+
+    /// Retrieves a stored response.
+    pub async fn retrieve(&self, response_id: &str) -> Result<Response, Error> {
+        self.client
+            .http_client
+            .get(&format!("/responses/{response_id}"), &[])
+            .await
+    }
+
+    pub async fn delete(&self, response_id: &str) -> Result<serde_json::Value, Error> {
+        self.client
+            .http_client
+            .delete(&format!("/responses/{response_id}"))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use reqwest::{header::HeaderMap, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::json;
+
+    use crate::{
+        http::{stream::stream, HttpBody, HttpRequest, HttpResponse},
+        middleware::MiddlewareStack,
+        moderations::ModerationGuard,
+        providers::OpenAIConfig,
+        rate_limit::RateLimiter,
+        ChatMessage, ChatRequest, OpenAIProvider,
+    };
+
+    use super::*;
+
+    /// Flags moderation inputs that mention `kill`, and answers `I will kill` to the input `bad` and `Hello` otherwise.
+    #[derive(Debug, Clone)]
+    struct MockHttpClient;
+
+    fn output(input: &serde_json::Value) -> &'static str {
+        match input.as_str() {
+            Some("bad") => "I will kill",
+            _ => "Hello",
+        }
+    }
+
+    fn response(text: &str) -> serde_json::Value {
+        json!({
+            "id": "resp_1",
+            "model": "gpt-4o-mini",
+            "status": "completed",
+            "output": [{"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": text}]}]
+        })
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+            let HttpBody::Json(body) = &request.body else {
+                return Err(Error::InvalidArgument("expected a JSON body".into()));
+            };
+            let body = match request.path.as_str() {
+                "/moderations" => {
+                    let flagged = body["input"].as_str().unwrap().contains("kill");
+                    json!({"results": [{"flagged": flagged, "categories": {"violence": flagged}, "category_scores": {}}]})
+                }
+                _ => response(output(&body["input"])),
+            };
+            Ok(HttpResponse {
+                url: request.path,
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from(body.to_string()),
+                time_to_first_byte: None,
+                latency: None,
+            })
+        }
+
+        async fn post_stream<I: Serialize + Send, O: DeserializeOwned + Send + 'static>(
+            &self,
+            _path: &str,
+            request: I,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<O, Error>> + Send>>, Error> {
+            let text = output(&serde_json::to_value(request)?["input"]);
+            let events = [
+                json!({"type": "response.output_text.delta", "item_id": "msg_1", "output_index": 0, "content_index": 0, "delta": text}),
+                json!({"type": "response.completed", "response": response(text)}),
+            ];
+            // Like the API, the connection is closed without `[DONE]`.
+            let body = events.map(|event| Ok(Bytes::from(format!("data: {event}\n\n"))));
+            Ok(stream(
+                Box::pin(futures::stream::iter(body)),
+                "[DONE]",
+                MiddlewareStack::new(),
+            ))
+        }
+
+        fn with_rate_limiter(self, _rate_limiter: RateLimiter) -> Self {
+            self
+        }
+
+        fn with_middleware(self, _middleware: MiddlewareStack) -> Self {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_moderation_works() {
+        let config = OpenAIConfig::new("https://api.openai.com/v1", None);
+        let client = Client::with_args(OpenAIProvider::new(config), MockHttpClient)
+            .with_moderation_guard(ModerationGuard::reject().with_output(true));
+        let responses = client.responses();
+
+        let response = responses
+            .create(ResponseRequest::new("gpt-4o-mini", "hi"))
+            .await
+            .unwrap();
+        assert_eq!(response.output_text(), "Hello");
+        let result = responses
+            .create(ResponseRequest::new("gpt-4o-mini", "kill"))
+            .await;
+        assert!(matches!(result, Err(Error::Moderation(_))));
+        let result = responses
+            .create(ResponseRequest::new("gpt-4o-mini", "bad"))
+            .await;
+        assert!(matches!(result, Err(Error::Moderation(_))));
+
+        let events: Vec<_> = responses
+            .create_stream(ResponseRequest::new("gpt-4o-mini", "hi"))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(events.len(), 2);
+        assert!(events[1].as_ref().unwrap().is_terminal());
+        let events: Vec<_> = responses
+            .create_stream(ResponseRequest::new("gpt-4o-mini", "bad"))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2], Err(Error::Moderation(_))));
+    }
+
+    #[test]
+    fn response_works() {
+        let json = r#"{"id":"resp_1","object":"response","created_at":1741476542,"status":"completed","model":"gpt-4o-2024-08-06","output":[{"type":"message","id":"msg_1","status":"completed","role":"assistant","content":[{"type":"output_text","text":"In a peaceful grove...","annotations":[]}]},{"type":"function_call","id":"fc_1","call_id":"call_1","name":"get_weather","arguments":"{\"location\":\"Paris\"}","status":"completed"},{"type":"mcp_call","id":"mcp_1"}],"usage":{"input_tokens":36,"input_tokens_details":{"cached_tokens":0},"output_tokens":87,"output_tokens_details":{"reasoning_tokens":0},"total_tokens":123}}"#;
+        let response: Response = serde_json::from_str(json).unwrap();
+        assert_eq!(response.output_text(), "In a peaceful grove...");
+        assert_eq!(response.usage().unwrap().total_tokens, Some(123));
+
+        let message = ChatMessage::from(&response);
+        let items = ResponseInputItem::from_messages([&message]);
+        assert_eq!(items.len(), 2);
+        assert!(
+            matches!(&items[1], ResponseInputItem::FunctionCall { call_id, .. } if call_id == "call_1")
+        );
+
+        let event: ResponseStreamEvent = serde_json::from_str(
+            r#"{"type":"response.output_text.delta","item_id":"msg_1","output_index":0,"content_index":0,"delta":"In","sequence_number":4}"#,
+        )
+        .unwrap();
+        assert_eq!(event.text_delta(), Some("In"));
+
+        let request = ChatRequest::new(
+            "gpt-4o-mini",
+            vec![ChatMessage::system("Be brief."), ChatMessage::user("Hi")],
+        );
+        let request = ResponseRequest::from(&request);
+        assert_eq!(
+            serde_json::to_value(&request.input).unwrap(),
+            serde_json::json!([
+                {"type": "message", "role": "system", "content": "Be brief."},
+                {"type": "message", "role": "user", "content": "Hi"}
+            ])
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::TokenCounter,
+    moderations::{ModerationInput, ModerationInputPart},
+    request::Requestable,
+    types::{ImageDetail, ImageUrl, ReasoningEffort},
+};
+
+/// The input of a response: a plain text user message or a list of items.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseInput {
+    Text(String),
+    Items(Vec<ResponseInputItem>),
+}
+
+impl Default for ResponseInput {
+    fn default() -> Self {
+        Self::Items(vec![])
+    }
+}
+
+impl From<&str> for ResponseInput {
+    fn from(value: &str) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+impl From<String> for ResponseInput {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<ResponseInputItem>> for ResponseInput {
+    fn from(value: Vec<ResponseInputItem>) -> Self {
+        Self::Items(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseRole {
+    User,
+    Assistant,
+    System,
+    Developer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseInputItem {
+    Message {
+        role: ResponseRole,
+        content: ResponseMessageContent,
+    },
+    /// A function call of a previous response, as returned in [`super::ResponseOutputItem::FunctionCall`].
+    FunctionCall {
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+    /// The result of a function call.
+    FunctionCallOutput { call_id: String, output: String },
+    /// A reference to an item of a stored response.
+    ItemReference { id: String },
+}
+
+impl ResponseInputItem {
+    pub fn message(role: ResponseRole, content: impl Into<ResponseMessageContent>) -> Self {
+        Self::Message {
+            role,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<ResponseMessageContent>) -> Self {
+        Self::message(ResponseRole::User, content)
+    }
+
+    pub fn function_call_output(call_id: impl Into<String>, output: impl Into<String>) -> Self {
+        Self::FunctionCallOutput {
+            call_id: call_id.into(),
+            output: output.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseMessageContent {
+    Text(String),
+    Parts(Vec<ResponseContentPart>),
+}
+
+impl From<&str> for ResponseMessageContent {
+    fn from(value: &str) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+impl From<String> for ResponseMessageContent {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<ResponseContentPart>> for ResponseMessageContent {
+    fn from(value: Vec<ResponseContentPart>) -> Self {
+        Self::Parts(value)
+    }
+}
+
+/// A content part of an input or output message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseContentPart {
+    InputText {
+        text: String,
+    },
+    InputImage {
+        /// A URL or a base64 data URL.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        image_url: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        detail: Option<ImageDetail>,
+    },
+    InputFile {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_data: Option<String>,
+    },
+    OutputText {
+        text: String,
+        #[serde(default)]
+        annotations: Vec<serde_json::Value>,
+    },
+    Refusal {
+        refusal: String,
+    },
+}
+
+/// A tool the model may use. Built-in tools take their options as in the API reference.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseTool {
+    Function {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parameters: Option<serde_json::Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        strict: Option<bool>,
+    },
+    WebSearchPreview {
+        #[serde(flatten)]
+        options: serde_json::Map<String, serde_json::Value>,
+    },
+    FileSearch {
+        vector_store_ids: Vec<String>,
+        #[serde(flatten)]
+        options: serde_json::Map<String, serde_json::Value>,
+    },
+    CodeInterpreter {
+        #[serde(flatten)]
+        options: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl ResponseTool {
+    pub fn web_search() -> Self {
+        Self::WebSearchPreview {
+            options: Default::default(),
+        }
+    }
+
+    pub fn file_search(vector_store_ids: Vec<String>) -> Self {
+        Self::FileSearch {
+            vector_store_ids,
+            options: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResponseReasoning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
+
+    /// `auto`, `concise` or `detailed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// A request to `/responses`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResponseRequest {
+    pub model: String,
+
+    pub input: ResponseInput,
+
+    /// A system (or developer) message inserted into the model's context. It is not carried over with `previous_response_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// Continues the conversation of a stored response instead of resending its history.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ResponseTool>>,
+
+    /// `none`, `auto`, `required` or a specific tool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// An upper bound for the number of tokens generated, including reasoning tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ResponseReasoning>,
+
+    /// Text output options, e.g. `{"format": {"type": "json_schema", ...}}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<serde_json::Value>,
+
+    /// Whether to store the response so it can be retrieved and continued later. Defaults to true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+
+    /// `auto` or `disabled`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl ResponseRequest {
+    pub fn new(model: impl Into<String>, input: impl Into<ResponseInput>) -> Self {
+        Self {
+            model: model.into(),
+            input: input.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    pub fn with_previous_response_id(mut self, previous_response_id: impl Into<String>) -> Self {
+        self.previous_response_id = Some(previous_response_id.into());
+        self
+    }
+
+    pub fn with_tools(mut self, tools: Vec<ResponseTool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    pub fn with_max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    pub fn with_store(mut self, store: bool) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn with_stream(mut self) -> Self {
+        self.stream = Some(true);
+        self
+    }
+}
+
+impl Requestable for ResponseRequest {
+    fn stream(&self) -> bool {
+        self.stream.unwrap_or(false)
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn estimate_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        let input = serde_json::to_string(&self.input).unwrap_or_default();
+        let instructions = self.instructions.as_deref().unwrap_or_default();
+        counter.count_text(&input)
+            + counter.count_text(instructions)
+            + self.max_output_tokens.unwrap_or(0) as usize
+    }
+
+    /// The text input, or the text and images of the last user message.
+    fn moderation_input(&self) -> Option<ModerationInput> {
+        let items = match &self.input {
+            ResponseInput::Text(text) => return Some(text.as_str().into()),
+            ResponseInput::Items(items) => items,
+        };
+        let content = items.iter().rev().find_map(|item| match item {
+            ResponseInputItem::Message {
+                role: ResponseRole::User,
+                content,
+            } => Some(content),
+            _ => None,
+        })?;
+        match content {
+            ResponseMessageContent::Text(text) => Some(text.as_str().into()),
+            ResponseMessageContent::Parts(parts) => Some(ModerationInput::Parts(
+                parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        ResponseContentPart::InputText { text } => {
+                            Some(ModerationInputPart::Text { text: text.clone() })
+                        }
+                        ResponseContentPart::InputImage {
+                            image_url: Some(url),
+                            detail,
+                            ..
+                        } => Some(ModerationInputPart::ImageUrl {
+                            image_url: ImageUrl {
+                                url: url.clone(),
+                                detail: detail.clone(),
+                            },
+                        }),
+                        _ => None,
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        self.max_output_tokens
+    }
+
+    fn prompt(&self) -> Option<String> {
+        serde_json::to_string(&self.input).ok()
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    response::Respondable,
+    types::{CompletionTokensDetails, CompletionUsage, PromptTokensDetails},
+};
+
+use super::{ResponseContentPart, ResponseRole};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseStatus {
+    Queued,
+    InProgress,
+    Completed,
+    Incomplete,
+    Failed,
+    Cancelled,
+}
+
+/// A response of `/responses`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Response {
+    pub id: String,
+
+    /// The object type, which is always `response`.
+    pub object: Option<String>,
+
+    /// The Unix timestamp (in seconds) of when the response was created.
+    pub created_at: Option<u64>,
+
+    pub status: Option<ResponseStatus>,
+
+    pub model: Option<String>,
+
+    #[serde(default)]
+    pub output: Vec<ResponseOutputItem>,
+
+    pub usage: Option<ResponseUsage>,
+
+    pub previous_response_id: Option<String>,
+
+    pub error: Option<ResponseError>,
+
+    /// Why the response is incomplete, e.g. `{"reason": "max_output_tokens"}`.
+    pub incomplete_details: Option<serde_json::Value>,
+
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Response {
+    /// The concatenated text of all output messages.
+    pub fn output_text(&self) -> String {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                ResponseOutputItem::Message { content, .. } => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|part| match part {
+                ResponseContentPart::OutputText { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The function calls the model made, as `(call_id, name, arguments)`.
+    pub fn function_calls(&self) -> impl Iterator<Item = (&str, &str, &str)> {
+        self.output.iter().filter_map(|item| match item {
+            ResponseOutputItem::FunctionCall {
+                call_id,
+                name,
+                arguments,
+                ..
+            } => Some((call_id.as_str(), name.as_str(), arguments.as_str())),
+            _ => None,
+        })
+    }
+}
+
+impl Respondable for Response {
+    fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    fn usage(&self) -> Option<CompletionUsage> {
+        self.usage.clone().map(Into::into)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponseError {
+    pub code: Option<String>,
+    pub message: Option<String>,
+}
+
+/// An item generated by the model.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseOutputItem {
+    Message {
+        id: Option<String>,
+        role: Option<ResponseRole>,
+        status: Option<String>,
+        #[serde(default)]
+        content: Vec<ResponseContentPart>,
+    },
+    FunctionCall {
+        id: Option<String>,
+        call_id: String,
+        name: String,
+        #[serde(default)]
+        arguments: String,
+        status: Option<String>,
+    },
+    Reasoning {
+        id: Option<String>,
+        #[serde(default)]
+        summary: Vec<serde_json::Value>,
+    },
+    WebSearchCall {
+        id: Option<String>,
+        status: Option<String>,
+    },
+    FileSearchCall {
+        id: Option<String>,
+        status: Option<String>,
+        queries: Option<Vec<String>>,
+        results: Option<Vec<serde_json::Value>>,
+    },
+    /// An item type unknown to this crate.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResponseUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub input_tokens_details: Option<ResponseInputTokensDetails>,
+    pub output_tokens_details: Option<ResponseOutputTokensDetails>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResponseInputTokensDetails {
+    pub cached_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResponseOutputTokensDetails {
+    pub reasoning_tokens: Option<u32>,
+}
+
+impl From<ResponseUsage> for CompletionUsage {
+    fn from(value: ResponseUsage) -> Self {
+        Self {
+            completion_tokens: Some(value.output_tokens),
+            prompt_tokens: Some(value.input_tokens),
+            total_tokens: Some(value.total_tokens),
+            completion_tokens_details: value.output_tokens_details.map(|details| {
+                CompletionTokensDetails {
+                    reasoning_tokens: details.reasoning_tokens,
+                    ..Default::default()
+                }
+            }),
+            prompt_tokens_details: value
+                .input_tokens_details
+                .map(|details| PromptTokensDetails {
+                    cached_tokens: details.cached_tokens,
+                    ..Default::default()
+                }),
+        }
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt};
+use tracing::{field::Empty, Span};
+
+use crate::{error::Error, request::Requestable, response::Respondable, types::CompletionUsage};
+
+/// How calls are instrumented. Spans follow the OpenTelemetry GenAI semantic conventions and are emitted with `tracing` under the `async_llm` target; with the `metrics` feature, counters and histograms are emitted with the `metrics` facade.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Telemetry {
+    /// Records the prompt and the completion as the `gen_ai.prompt` and `gen_ai.completion` span fields. Off by default, as they may contain sensitive data.
+    pub capture_content: bool,
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capture_content(mut self, capture_content: bool) -> Self {
+        self.capture_content = capture_content;
+        self
+    }
+}
+
+/// The `gen_ai.system` of a provider name, e.g. `openai` for `api.openai.com`.
+fn gen_ai_system(provider: &str) -> &str {
+    match provider {
+        "api.openai.com" => "openai",
+        "openrouter.ai" => "openrouter",
+        "generativelanguage.googleapis.com" => "gemini",
+        "api.anthropic.com" => "anthropic",
+        "api.mistral.ai" => "mistral_ai",
+        "api.groq.com" => "groq",
+        "api.deepseek.com" => "deepseek",
+        provider if provider.ends_with(":11434") => "ollama",
+        provider => provider,
+    }
+}
+
+/// The `error.type` of an error.
+fn error_type(error: &Error) -> String {
+    match error {
+        Error::Api(error) => error
+            .code
+            .clone()
+            .or_else(|| error.status.map(|status| status.to_string()))
+            .unwrap_or_else(|| "api".into()),
+        Error::InvalidArgument(_) => "invalid_argument".into(),
+        Error::MissingApiKey => "missing_api_key".into(),
+        Error::InvalidConfig(_) => "invalid_config".into(),
+        Error::ContextLengthExceeded(_) => "context_length_exceeded".into(),
+        Error::BudgetExceeded(_) => "budget_exceeded".into(),
+        Error::Moderation(_) => "moderation".into(),
+        Error::Refusal(_) => "refusal".into(),
+        Error::HttpClient(_) => "http_client".into(),
+        Error::Transport(_) => "transport".into(),
+        Error::Stream(_) => "stream".into(),
+        Error::Cancelled => "cancelled".into(),
+        Error::Batch(_) => "batch".into(),
+        Error::Json(_) => "json".into(),
+        Error::Io(_) => "io".into(),
+    }
+}
+
+/// The span and measurements of one call.
+pub(crate) struct Instrumentation {
+    span: Span,
+    start: Instant,
+    telemetry: Telemetry,
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    labels: Vec<(&'static str, String)>,
+}
+
+impl Instrumentation {
+    pub(crate) fn start(
+        operation: &'static str,
+        provider: &str,
+        request: &impl Requestable,
+        telemetry: Telemetry,
+    ) -> Self {
+        let model = request.model().unwrap_or_default();
+        let system = gen_ai_system(provider);
+        let span = tracing::info_span!(
+            target: "async_llm",
+            "gen_ai",
+            otel.name = %format!("{operation} {model}"),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            gen_ai.operation.name = operation,
+            gen_ai.system = system,
+            server.address = provider,
+            gen_ai.request.model = model,
+            gen_ai.request.temperature = Empty,
+            gen_ai.request.max_tokens = Empty,
+            gen_ai.response.id = Empty,
+            gen_ai.response.model = Empty,
+            gen_ai.response.finish_reasons = Empty,
+            gen_ai.usage.input_tokens = Empty,
+            gen_ai.usage.output_tokens = Empty,
+            gen_ai.client.time_to_first_token = Empty,
+            gen_ai.client.tokens_per_second = Empty,
+            gen_ai.prompt = Empty,
+            gen_ai.completion = Empty,
+            error.type = Empty,
+        );
+        if let Some(temperature) = request.temperature() {
+            span.record("gen_ai.request.temperature", temperature as f64);
+        }
+        if let Some(max_tokens) = request.max_tokens() {
+            span.record("gen_ai.request.max_tokens", max_tokens);
+        }
+        if telemetry.capture_content {
+            if let Some(prompt) = request.prompt() {
+                span.record("gen_ai.prompt", prompt);
+            }
+        }
+        Self {
+            span,
+            start: Instant::now(),
+            telemetry,
+            labels: vec![
+                ("gen_ai.operation.name", operation.to_string()),
+                ("gen_ai.system", system.to_string()),
+                ("gen_ai.request.model", model.to_string()),
+            ],
+        }
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Records the result of a call that is not streamed.
+    pub(crate) fn finish<R: Respondable>(&self, result: Result<&R, &Error>) {
+        match result {
+            Ok(response) => {
+                self.record_response(
+                    response.id(),
+                    response.model(),
+                    &response.finish_reasons(),
+                    response.usage().as_ref(),
+                    response.text(),
+                );
+                self.record_end(None, None, None);
+            }
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// Records a call that failed before a response was received.
+    pub(crate) fn fail(&self, error: &Error) {
+        self.record_end(Some(error_type(error)), None, None);
+    }
+
+    fn record_response(
+        &self,
+        id: Option<&str>,
+        model: Option<&str>,
+        finish_reasons: &[&str],
+        usage: Option<&CompletionUsage>,
+        completion: Option<&str>,
+    ) {
+        let span = &self.span;
+        if let Some(id) = id {
+            span.record("gen_ai.response.id", id);
+        }
+        if let Some(model) = model {
+            span.record("gen_ai.response.model", model);
+        }
+        if !finish_reasons.is_empty() {
+            span.record(
+                "gen_ai.response.finish_reasons",
+                tracing::field::debug(finish_reasons),
+            );
+        }
+        if let Some(usage) = usage {
+            if let Some(tokens) = usage.prompt_tokens {
+                span.record("gen_ai.usage.input_tokens", tokens);
+            }
+            if let Some(tokens) = usage.completion_tokens {
+                span.record("gen_ai.usage.output_tokens", tokens);
+            }
+        }
+        if self.telemetry.capture_content {
+            if let Some(completion) = completion {
+                span.record("gen_ai.completion", completion);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(usage) = usage {
+            for (token_type, tokens) in [
+                ("input", usage.prompt_tokens),
+                ("output", usage.completion_tokens),
+            ] {
+                if let Some(tokens) = tokens {
+                    let mut labels = self.labels.clone();
+                    labels.push(("gen_ai.token.type", token_type.to_string()));
+                    metrics::histogram!("gen_ai.client.token.usage", &labels).record(tokens);
+                }
+            }
+        }
+    }
+
+    fn record_end(
+        &self,
+        error_type: Option<String>,
+        time_to_first_token: Option<Duration>,
+        tokens_per_second: Option<f64>,
+    ) {
+        let span = &self.span;
+        match &error_type {
+            Some(error_type) => {
+                span.record("otel.status_code", "ERROR");
+                span.record("error.type", error_type.as_str());
+            }
+            None => {
+                span.record("otel.status_code", "OK");
+            }
+        }
+        if let Some(time_to_first_token) = time_to_first_token {
+            span.record(
+                "gen_ai.client.time_to_first_token",
+                time_to_first_token.as_secs_f64(),
+            );
+        }
+        if let Some(tokens_per_second) = tokens_per_second {
+            span.record("gen_ai.client.tokens_per_second", tokens_per_second);
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let mut labels = self.labels.clone();
+            if let Some(error_type) = error_type {
+                labels.push(("error.type", error_type));
+            }
+            metrics::counter!("gen_ai.client.requests", &labels).increment(1);
+            metrics::histogram!("gen_ai.client.operation.duration", &labels)
+                .record(self.start.elapsed().as_secs_f64());
+            if let Some(time_to_first_token) = time_to_first_token {
+                metrics::histogram!("gen_ai.client.time_to_first_token", &self.labels)
+                    .record(time_to_first_token.as_secs_f64());
+            }
+        }
+    }
+
+    /// Instruments a stream: the span covers polling, and the response is recorded when the stream ends or is dropped.
+    pub(crate) fn stream<T>(
+        self,
+        stream: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>
+    where
+        T: Respondable + Send + 'static,
+    {
+        Box::pin(InstrumentedStream {
+            inner: stream,
+            instrumentation: self,
+            state: StreamState::default(),
+            done: false,
+        })
+    }
+}
+
+#[derive(Default)]
+struct StreamState {
+    id: Option<String>,
+    model: Option<String>,
+    finish_reasons: Vec<String>,
+    usage: Option<CompletionUsage>,
+    completion: String,
+    text_chunks: u32,
+    first_token: Option<Instant>,
+    error_type: Option<String>,
+}
+
+struct InstrumentedStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>,
+    instrumentation: Instrumentation,
+    state: StreamState,
+    done: bool,
+}
+
+impl<T> InstrumentedStream<T> {
+    fn finish(&mut self) {
+        if self.done {
+            return;
+        }
+        self.done = true;
+        let state = std::mem::take(&mut self.state);
+        let instrumentation = &self.instrumentation;
+        let _enter = instrumentation.span.enter();
+        let finish_reasons: Vec<&str> = state.finish_reasons.iter().map(String::as_str).collect();
+        instrumentation.record_response(
+            state.id.as_deref(),
+            state.model.as_deref(),
+            &finish_reasons,
+            state.usage.as_ref(),
+            Some(state.completion.as_str()).filter(|completion| !completion.is_empty()),
+        );
+        let time_to_first_token = state
+            .first_token
+            .map(|first_token| first_token - instrumentation.start);
+        // Without usage, each chunk with text is counted as one token.
+        let tokens = state
+            .usage
+            .as_ref()
+            .and_then(|usage| usage.completion_tokens)
+            .unwrap_or(state.text_chunks);
+        let tokens_per_second = state.first_token.and_then(|first_token| {
+            let elapsed = first_token.elapsed().as_secs_f64();
+            (elapsed > 0.0 && tokens > 0).then(|| tokens as f64 / elapsed)
+        });
+        instrumentation.record_end(state.error_type, time_to_first_token, tokens_per_second);
+    }
+}
+
+impl<T: Respondable> Stream for InstrumentedStream<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let item = {
+            let _enter = this.instrumentation.span.enter();
+            this.inner.poll_next_unpin(cx)
+        };
+        match &item {
+            Poll::Ready(Some(Ok(chunk))) => {
+                let state = &mut this.state;
+                if let Some(text) = chunk.text().filter(|text| !text.is_empty()) {
+                    state.first_token.get_or_insert_with(Instant::now);
+                    state.text_chunks += 1;
+                    if this.instrumentation.telemetry.capture_content {
+                        state.completion.push_str(text);
+                    }
+                }
+                if let Some(id) = chunk.id() {
+                    state.id.get_or_insert_with(|| id.to_string());
+                }
+                if let Some(model) = chunk.model() {
+                    state.model.get_or_insert_with(|| model.to_string());
+                }
+                state
+                    .finish_reasons
+                    .extend(chunk.finish_reasons().into_iter().map(str::to_string));
+                if let Some(usage) = chunk.usage() {
+                    state.usage = Some(usage);
+                }
+            }
+            Poll::Ready(Some(Err(e))) => {
+                this.state.error_type = Some(error_type(e));
+            }
+            Poll::Ready(None) => this.finish(),
+            Poll::Pending => {}
+        }
+        item
+    }
+}
+
+impl<T> Drop for InstrumentedStream<T> {
+    fn drop(&mut self) {
+        // A stream dropped before any choice finished was cancelled by the caller.
+        if !self.done && self.state.finish_reasons.is_empty() && self.state.error_type.is_none() {
+            self.state.error_type = Some(error_type(&Error::Cancelled));
+        }
+        self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::SubscriberExt, registry::LookupSpan, Layer, Registry};
+
+    use super::*;
+    use crate::{ChatMessage, ChatRequest, ChatResponseStream};
+
+    /// Collects the fields recorded on spans.
+    #[derive(Debug, Clone, Default)]
+    struct Capture(Arc<Mutex<HashMap<String, String>>>);
+
+    impl Capture {
+        fn get(&self, field: &str) -> Option<String> {
+            self.0.lock().unwrap().get(field).cloned()
+        }
+    }
+
+    impl Visit for Capture {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().into(), value.into());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().into(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(
+            &self,
+            attrs: &Attributes<'_>,
+            _id: &Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(
+            &self,
+            _id: &Id,
+            values: &Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    fn chunks() -> Vec<Result<ChatResponseStream, Error>> {
+        [
+            r#"{"id":"1","model":"gpt-4o-mini","choices":[{"index":0,"delta":{"content":"Hello"}}]}"#,
+            r#"{"id":"1","choices":[{"index":0,"delta":{},"finish_reason":"stop"}],"usage":{"prompt_tokens":8,"completion_tokens":2,"total_tokens":10}}"#,
+        ]
+        .iter()
+        .map(|chunk| Ok(serde_json::from_str(chunk).unwrap()))
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn instrumented_stream_works() {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+        let request = ChatRequest::new("gpt-4o-mini", vec![ChatMessage::user("Hi")]);
+        let instrumentation =
+            Instrumentation::start("chat", "api.openai.com", &request, Telemetry::new());
+        let stream = instrumentation.stream(Box::pin(futures::stream::iter(chunks())));
+        assert_eq!(stream.count().await, 2);
+        assert_eq!(capture.get("gen_ai.system").as_deref(), Some("openai"));
+        assert_eq!(capture.get("gen_ai.response.id").as_deref(), Some("1"));
+        assert_eq!(
+            capture.get("gen_ai.response.model").as_deref(),
+            Some("gpt-4o-mini")
+        );
+        assert_eq!(
+            capture.get("gen_ai.response.finish_reasons").as_deref(),
+            Some(r#"["stop"]"#)
+        );
+        assert_eq!(
+            capture.get("gen_ai.usage.input_tokens").as_deref(),
+            Some("8")
+        );
+        assert_eq!(
+            capture.get("gen_ai.usage.output_tokens").as_deref(),
+            Some("2")
+        );
+        assert!(capture.get("gen_ai.client.time_to_first_token").is_some());
+        assert_eq!(capture.get("otel.status_code").as_deref(), Some("OK"));
+        assert_eq!(capture.get("error.type"), None);
+        assert_eq!(capture.get("gen_ai.prompt"), None);
+        assert_eq!(gen_ai_system("localhost:11434"), "ollama");
+    }
+
+    #[tokio::test]
+    async fn instrumented_stream_cancelled_works() {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+        let request = ChatRequest::new("gpt-4o-mini", vec![ChatMessage::user("Hi")]);
+        let instrumentation = Instrumentation::start(
+            "chat",
+            "api.openai.com",
+            &request,
+            Telemetry::new().with_capture_content(true),
+        );
+        let mut stream = instrumentation.stream(Box::pin(futures::stream::iter(chunks())));
+        assert!(stream.next().await.unwrap().is_ok());
+        assert_eq!(capture.get("otel.status_code"), None);
+        drop(stream);
+        assert_eq!(capture.get("otel.status_code").as_deref(), Some("ERROR"));
+        assert_eq!(capture.get("error.type").as_deref(), Some("cancelled"));
+        assert_eq!(capture.get("gen_ai.response.id").as_deref(), Some("1"));
+        assert_eq!(capture.get("gen_ai.response.finish_reasons"), None);
+        assert_eq!(
+            capture.get("gen_ai.prompt").as_deref(),
+            Some(r#"[{"role":"user","content":"Hi"}]"#)
+        );
+        assert_eq!(capture.get("gen_ai.completion").as_deref(), Some("Hello"));
+        assert!(capture.get("gen_ai.client.time_to_first_token").is_some());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::OnceLock;
+
+use tiktoken_rs::CoreBPE;
+
+use super::Tokenizer;
+
+/// The byte pair encodings embedded in the crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Used by `gpt-4`, `gpt-3.5-turbo` and `text-embedding-3-*`.
+    Cl100kBase,
+    /// Used by `gpt-4o`, `gpt-4o-mini`, `gpt-4.1`, `o1`, `o3` and `o4-mini`.
+    O200kBase,
+}
+
+impl Encoding {
+    /// The encoding of an OpenAI model, or `None` if the model is unknown.
+    pub fn for_model(model: &str) -> Option<Self> {
+        // Strip provider prefixes such as `openai/gpt-4o` used by OpenRouter.
+        let model = model.rsplit('/').next().unwrap_or(model);
+        if [
+            "gpt-4o",
+            "chatgpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "o1",
+            "o3",
+            "o4",
+        ]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+        {
+            Some(Self::O200kBase)
+        } else if ["gpt-4", "gpt-3.5", "gpt-35", "text-embedding-", "ft:gpt-"]
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+        {
+            Some(Self::Cl100kBase)
+        } else {
+            None
+        }
+    }
+
+    fn bpe(&self) -> &'static CoreBPE {
+        static CL100K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+        static O200K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+        match self {
+            Self::Cl100kBase => CL100K_BASE.get_or_init(|| {
+                tiktoken_rs::cl100k_base().expect("the embedded cl100k_base vocabulary is valid")
+            }),
+            Self::O200kBase => O200K_BASE.get_or_init(|| {
+                tiktoken_rs::o200k_base().expect("the embedded o200k_base vocabulary is valid")
+            }),
+        }
+    }
+}
+
+/// A tokenizer for OpenAI models. The vocabulary is loaded once on first use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BpeTokenizer {
+    pub encoding: Encoding,
+}
+
+impl BpeTokenizer {
+    pub fn new(encoding: Encoding) -> Self {
+        Self { encoding }
+    }
+
+    /// The tokenizer of an OpenAI model, falling back to `o200k_base` for unknown models.
+    pub fn for_model(model: &str) -> Self {
+        Self::new(Encoding::for_model(model).unwrap_or(Encoding::O200kBase))
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.encoding.bpe().encode_with_special_tokens(text)
+    }
+
+    pub fn decode(&self, tokens: Vec<u32>) -> Option<String> {
+        self.encoding.bpe().decode(tokens).ok()
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn count(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_for_model_works() {
+        for model in [
+            "gpt-4o-mini",
+            "gpt-4.1",
+            "gpt-4.1-nano",
+            "openai/o4-mini",
+            "o3",
+        ] {
+            assert_eq!(
+                Encoding::for_model(model),
+                Some(Encoding::O200kBase),
+                "{model}"
+            );
+        }
+        for model in [
+            "gpt-4",
+            "gpt-4-turbo",
+            "gpt-3.5-turbo",
+            "text-embedding-3-small",
+        ] {
+            assert_eq!(
+                Encoding::for_model(model),
+                Some(Encoding::Cl100kBase),
+                "{model}"
+            );
+        }
+        assert_eq!(Encoding::for_model("llama-3.1-8b"), None);
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use crate::error::Error;
+
+use super::Tokenizer;
+
+/// A tokenizer loaded from a HuggingFace `tokenizer.json`, e.g. for Llama or Mistral models served via Ollama.
+///
+/// Chat templates differ between model families, so adjust the overheads of [`super::ChatTokenCounter`] to match the template of the model.
+#[derive(Debug, Clone)]
+pub struct HuggingFaceTokenizer {
+    tokenizer: tokenizers::Tokenizer,
+}
+
+impl HuggingFaceTokenizer {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let tokenizer = tokenizers::Tokenizer::from_file(path).map_err(|e| {
+            Error::InvalidConfig(format!("Failed to load tokenizer.json. Error = {e}"))
+        })?;
+        Ok(Self { tokenizer })
+    }
+
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let tokenizer = tokenizers::Tokenizer::from_bytes(bytes).map_err(|e| {
+            Error::InvalidConfig(format!("Failed to parse tokenizer.json. Error = {e}"))
+        })?;
+        Ok(Self { tokenizer })
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.tokenizer
+            .encode(text, false)
+            .map(|encoding| encoding.get_ids().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+impl Tokenizer for HuggingFaceTokenizer {
+    fn count(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+}
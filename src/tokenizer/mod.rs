@@ -0,0 +1,323 @@
+use crate::{
+    context::TokenCounter,
+    types::{
+        AssistantContent, AssistantContentPart, ChatTool, Content, ImageDetail, UserContent,
+        UserContentPart,
+    },
+    ChatMessage, ChatRequest,
+};
+
+#[cfg(feature = "tiktoken")]
+pub mod bpe;
+#[cfg(feature = "hf-tokenizers")]
+pub mod huggingface;
+
+#[cfg(feature = "tiktoken")]
+pub use bpe::{BpeTokenizer, Encoding};
+#[cfg(feature = "hf-tokenizers")]
+pub use huggingface::HuggingFaceTokenizer;
+
+/// Counts the tokens of a text.
+pub trait Tokenizer: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// A rough estimate of about 4 characters per token that works without any vocabulary.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharTokenizer;
+
+impl Tokenizer for CharTokenizer {
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Estimates the prompt tokens of chat requests the way OpenAI counts them: a fixed overhead per message and per name, the reply priming, the tool definitions and image tiles.
+///
+/// See: https://cookbook.openai.com/examples/how_to_count_tokens_with_tiktoken
+#[derive(Debug, Clone)]
+pub struct ChatTokenCounter<T: Tokenizer> {
+    pub tokenizer: T,
+
+    /// The tokens added for every message by the chat template.
+    pub tokens_per_message: usize,
+
+    /// The tokens added when a message has a `name`.
+    pub tokens_per_name: usize,
+
+    /// The tokens that prime the assistant reply.
+    pub reply_priming: usize,
+
+    /// The image size assumed when it is unknown, e.g. for image URLs. Defaults to 1024x1024.
+    pub image_size: (u32, u32),
+}
+
+impl<T: Tokenizer> ChatTokenCounter<T> {
+    pub fn new(tokenizer: T) -> Self {
+        Self {
+            tokenizer,
+            tokens_per_message: 3,
+            tokens_per_name: 1,
+            reply_priming: 3,
+            image_size: (1024, 1024),
+        }
+    }
+
+    pub fn with_tokens_per_message(mut self, tokens: usize) -> Self {
+        self.tokens_per_message = tokens;
+        self
+    }
+
+    pub fn with_tokens_per_name(mut self, tokens: usize) -> Self {
+        self.tokens_per_name = tokens;
+        self
+    }
+
+    pub fn with_reply_priming(mut self, tokens: usize) -> Self {
+        self.reply_priming = tokens;
+        self
+    }
+
+    pub fn with_image_size(mut self, width: u32, height: u32) -> Self {
+        self.image_size = (width, height);
+        self
+    }
+
+    fn count_content(&self, content: &Content) -> usize {
+        match content {
+            Content::Text(text) => self.tokenizer.count(text),
+            Content::Array(parts) => parts.iter().map(|part| self.tokenizer.count(part)).sum(),
+        }
+    }
+
+    fn count_user_content(&self, content: &UserContent) -> usize {
+        match content {
+            UserContent::Text(text) => self.tokenizer.count(text),
+            UserContent::Array(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    UserContentPart::Text { text } => self.tokenizer.count(text),
+                    UserContentPart::ImageUrl { image_url } => {
+                        let (width, height) = self.image_size;
+                        image_tokens(
+                            image_url.detail.as_ref().unwrap_or(&ImageDetail::Auto),
+                            width,
+                            height,
+                        )
+                    }
+                    // Audio input is billed separately as audio tokens.
+                    UserContentPart::Audio { .. } => 0,
+                })
+                .sum(),
+        }
+    }
+
+    fn count_assistant_content(&self, content: &AssistantContent) -> usize {
+        match content {
+            AssistantContent::Text(text) => self.tokenizer.count(text),
+            AssistantContent::Array(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    AssistantContentPart::Text(text) => self.tokenizer.count(text),
+                    AssistantContentPart::Refusal { refusal } => self.tokenizer.count(refusal),
+                })
+                .sum(),
+        }
+    }
+
+    /// The tokens of the tool definitions, following the format OpenAI renders them into the system prompt.
+    pub fn count_tools(&self, tools: &[ChatTool]) -> usize {
+        if tools.is_empty() {
+            return 0;
+        }
+        let mut tokens = 0;
+        for ChatTool::Function { function } in tools {
+            tokens += 7;
+            let description = function.description.as_deref().unwrap_or_default();
+            tokens += self.tokenizer.count(&format!(
+                "{}:{}",
+                function.name,
+                description.trim_end_matches('.')
+            ));
+            let properties = function
+                .parameters
+                .as_ref()
+                .and_then(|parameters| parameters.get("properties"))
+                .and_then(|properties| properties.as_object());
+            let Some(properties) = properties.filter(|p| !p.is_empty()) else {
+                continue;
+            };
+            tokens += 3;
+            for (key, property) in properties {
+                tokens += 3;
+                let r#type = property
+                    .get("type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default();
+                let description = property
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default();
+                if let Some(items) = property.get("enum").and_then(|e| e.as_array()) {
+                    tokens = tokens.saturating_sub(3);
+                    for item in items {
+                        tokens += 3 + self.tokenizer.count(&item.to_string());
+                    }
+                }
+                tokens += self.tokenizer.count(&format!(
+                    "{key}:{type}:{}",
+                    description.trim_end_matches('.')
+                ));
+            }
+        }
+        tokens + 12
+    }
+}
+
+impl<T: Tokenizer> TokenCounter for ChatTokenCounter<T> {
+    #[allow(deprecated)]
+    fn count_message(&self, message: &ChatMessage) -> usize {
+        let count = |text: &str| self.tokenizer.count(text);
+        let name_tokens = |name: &Option<String>| {
+            name.as_ref()
+                .map(|name| self.tokens_per_name + count(name))
+                .unwrap_or(0)
+        };
+        self.tokens_per_message
+            + match message {
+                ChatMessage::Developer { content, name } => {
+                    count("developer") + self.count_content(content) + name_tokens(name)
+                }
+                ChatMessage::System { content, name } => {
+                    count("system") + self.count_content(content) + name_tokens(name)
+                }
+                ChatMessage::User { content, name } => {
+                    count("user") + self.count_user_content(content) + name_tokens(name)
+                }
+                ChatMessage::Assistant {
+                    content,
+                    refusal,
+                    name,
+                    tool_calls,
+                    function_call,
+                    ..
+                } => {
+                    count("assistant")
+                        + content
+                            .as_ref()
+                            .map(|content| self.count_assistant_content(content))
+                            .unwrap_or(0)
+                        + refusal.as_deref().map(count).unwrap_or(0)
+                        + name_tokens(name)
+                        + tool_calls
+                            .iter()
+                            .flatten()
+                            .map(|tool_call| {
+                                count(&tool_call.function.name)
+                                    + count(&tool_call.function.arguments)
+                            })
+                            .sum::<usize>()
+                        + function_call
+                            .as_ref()
+                            .map(|f| count(&f.name) + count(&f.arguments))
+                            .unwrap_or(0)
+                }
+                ChatMessage::Tool { content, .. } => count("tool") + self.count_content(content),
+            }
+    }
+
+    fn count_request(&self, request: &ChatRequest) -> usize {
+        request
+            .messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum::<usize>()
+            + request
+                .tools
+                .as_ref()
+                .map(|tools| self.count_tools(tools))
+                .unwrap_or(0)
+            + self.reply_priming
+    }
+
+    fn count_text(&self, text: &str) -> usize {
+        self.tokenizer.count(text)
+    }
+}
+
+/// The tokens of an image input of the given size.
+///
+/// `low` detail costs a fixed 85 tokens. Otherwise the image is scaled to fit into 2048x2048, then so that its shortest side is at most 768, and every 512px tile costs 170 tokens on top of the base 85.
+pub fn image_tokens(detail: &ImageDetail, width: u32, height: u32) -> usize {
+    if *detail == ImageDetail::Low {
+        return 85;
+    }
+    let (mut width, mut height) = (width.max(1) as f64, height.max(1) as f64);
+    let longest = width.max(height);
+    if longest > 2048.0 {
+        width *= 2048.0 / longest;
+        height *= 2048.0 / longest;
+    }
+    let shortest = width.min(height);
+    if shortest > 768.0 {
+        width *= 768.0 / shortest;
+        height *= 768.0 / shortest;
+    }
+    let tiles = (width / 512.0).ceil() * (height / 512.0).ceil();
+    85 + 170 * tiles as usize
+}
+
+impl Default for ChatTokenCounter<CharTokenizer> {
+    fn default() -> Self {
+        Self::new(CharTokenizer)
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl ChatTokenCounter<BpeTokenizer> {
+    /// A counter using the encoding of the given OpenAI model, falling back to `o200k_base` for unknown models.
+    pub fn for_model(model: &str) -> Self {
+        Self::new(BpeTokenizer::for_model(model))
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl ChatRequest {
+    /// Estimates the prompt tokens of this request locally, using the encoding of its model.
+    pub fn count_tokens(&self) -> usize {
+        ChatTokenCounter::for_model(&self.model).count_request(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_tokens_works() {
+        assert_eq!(image_tokens(&ImageDetail::Low, 4096, 8192), 85);
+        assert_eq!(image_tokens(&ImageDetail::High, 1024, 1024), 765);
+        assert_eq!(image_tokens(&ImageDetail::High, 2048, 4096), 1105);
+    }
+
+    #[test]
+    fn char_token_counter_works() {
+        let counter = ChatTokenCounter::default();
+        assert_eq!(counter.count_text("who are you?"), 3);
+        let request = ChatRequest::new("gpt-4o-mini", vec![ChatMessage::user("who are you?")]);
+        assert_eq!(counter.count_request(&request), 10);
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn count_tokens_works() {
+        // See data/who_are_you/openai_gpt-4o-mini/response.json
+        let request = ChatRequest::new("gpt-4o-mini", vec![ChatMessage::user("who are you?")]);
+        assert_eq!(request.count_tokens(), 11);
+
+        let request = request
+            .with_model("gpt-4")
+            .append_user("What's the weather like in Vietnam?");
+        assert_eq!(request.count_tokens(), 23);
+    }
+}
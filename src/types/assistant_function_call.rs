@@ -1,4 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    json::{JsonRepair, RepairReport},
+    Error,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AssistantFunctionCall {
@@ -8,3 +13,18 @@ pub struct AssistantFunctionCall {
     /// The arguments to call the function with, as generated by the model in JSON format. Note that the model does not always generate valid JSON, and may hallucinate parameters not defined by your function schema. Validate the arguments in your code before calling your function.
     pub arguments: String,
 }
+
+impl AssistantFunctionCall {
+    /// Parses the arguments, repairing common mistakes with [`JsonRepair`]. Empty arguments are parsed as `{}`.
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        self.parse_arguments_with(&JsonRepair::new())
+            .map(|(value, _)| value)
+    }
+
+    pub fn parse_arguments_with<T: DeserializeOwned>(
+        &self,
+        repair: &JsonRepair,
+    ) -> Result<(T, RepairReport), Error> {
+        repair.parse_arguments(&self.arguments)
+    }
+}
@@ -8,9 +8,15 @@ pub enum ChatAudioVoice {
     Ballad,
     Coral,
     Echo,
+    Fable,
+    Onyx,
+    Nova,
     Sage,
     Shimmer,
     Verse,
+    /// A voice unknown to this crate, e.g. of a local TTS server like Kokoro.
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -21,6 +27,10 @@ pub enum ChatAudioFormat {
     Flac,
     Opus,
     Pcm16,
+    /// Only for speech. Raw samples in 24kHz 16-bit signed little-endian without a header.
+    Pcm,
+    /// Only for speech.
+    Aac,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1,4 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    json::{JsonRepair, RepairReport},
+    Error,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChatChoice {
@@ -30,7 +35,7 @@ pub struct ChatChoiceStream {
     pub logprobs: Option<ChatLogprobs>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ChatLogprobs {
     /// A list of message content tokens with log probability information.
     pub content: Option<Vec<ChatLogprobsMessage>>,
@@ -61,7 +66,7 @@ pub struct ChatLogprobsLogProb {
     pub bytes: Option<Vec<u8>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ChatChoiceMessage {
     /// The contents of the message.
     pub content: Option<String>,
@@ -102,6 +107,10 @@ pub struct ChatChoiceMessageStream {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChatMessageToolCall {
+    /// The index of the tool call in the list of tool calls. Only present in streamed deltas.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+
     /// The ID of the tool call.
     pub id: Option<String>,
     /// The type of the tool. Currently, only function is supported.
@@ -111,7 +120,7 @@ pub struct ChatMessageToolCall {
     pub function: Option<ChatMessageFunctionCall>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ChatMessageFunctionCall {
     /// The name of the function to call.
     pub name: Option<String>,
@@ -120,6 +129,21 @@ pub struct ChatMessageFunctionCall {
     pub arguments: Option<String>,
 }
 
+impl ChatMessageFunctionCall {
+    /// Parses the arguments, repairing common mistakes with [`JsonRepair`]. Missing arguments are parsed as `{}`.
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        self.parse_arguments_with(&JsonRepair::new())
+            .map(|(value, _)| value)
+    }
+
+    pub fn parse_arguments_with<T: DeserializeOwned>(
+        &self,
+        repair: &JsonRepair,
+    ) -> Result<(T, RepairReport), Error> {
+        repair.parse_arguments(self.arguments.as_deref().unwrap_or_default())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChatMessageAudio {
     /// Unique identifier for this audio response.
@@ -1,6 +1,8 @@
+use std::ops::{Add, AddAssign};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct CompletionUsage {
     /// Number of tokens in the generated completion.
     pub completion_tokens: Option<u32>,
@@ -28,9 +30,15 @@ pub struct CompletionUsageStream {
 
     /// Total number of tokens used in the request (prompt + completion).
     pub total_tokens: Option<u32>,
+
+    /// Breakdown of tokens used in a completion.
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
+
+    /// Breakdown of tokens used in the prompt.
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct CompletionTokensDetails {
     /// When using Predicted Outputs, the number of tokens in the prediction that appeared in the completion.
     pub accepted_prediction_tokens: Option<u32>,
@@ -45,7 +53,7 @@ pub struct CompletionTokensDetails {
     pub rejected_prediction_tokens: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct PromptTokensDetails {
     /// Audio input tokens present in the prompt.
     pub audio_tokens: Option<u32>,
@@ -53,3 +61,91 @@ pub struct PromptTokensDetails {
     /// Cached tokens present in the prompt.
     pub cached_tokens: Option<u32>,
 }
+
+impl From<CompletionUsageStream> for CompletionUsage {
+    fn from(value: CompletionUsageStream) -> Self {
+        Self {
+            completion_tokens: value.completion_tokens,
+            prompt_tokens: value.prompt_tokens,
+            total_tokens: value.total_tokens,
+            completion_tokens_details: value.completion_tokens_details,
+            prompt_tokens_details: value.prompt_tokens_details,
+        }
+    }
+}
+
+fn add_tokens(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0).saturating_add(b.unwrap_or(0))),
+    }
+}
+
+fn add_details<T: Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Add for CompletionUsage {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            completion_tokens: add_tokens(self.completion_tokens, rhs.completion_tokens),
+            prompt_tokens: add_tokens(self.prompt_tokens, rhs.prompt_tokens),
+            total_tokens: add_tokens(self.total_tokens, rhs.total_tokens),
+            completion_tokens_details: add_details(
+                self.completion_tokens_details,
+                rhs.completion_tokens_details,
+            ),
+            prompt_tokens_details: add_details(
+                self.prompt_tokens_details,
+                rhs.prompt_tokens_details,
+            ),
+        }
+    }
+}
+
+impl AddAssign for CompletionUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = std::mem::take(self) + rhs;
+    }
+}
+
+impl std::iter::Sum for CompletionUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl Add for CompletionTokensDetails {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            accepted_prediction_tokens: add_tokens(
+                self.accepted_prediction_tokens,
+                rhs.accepted_prediction_tokens,
+            ),
+            audio_tokens: add_tokens(self.audio_tokens, rhs.audio_tokens),
+            reasoning_tokens: add_tokens(self.reasoning_tokens, rhs.reasoning_tokens),
+            rejected_prediction_tokens: add_tokens(
+                self.rejected_prediction_tokens,
+                rhs.rejected_prediction_tokens,
+            ),
+        }
+    }
+}
+
+impl Add for PromptTokensDetails {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            audio_tokens: add_tokens(self.audio_tokens, rhs.audio_tokens),
+            cached_tokens: add_tokens(self.cached_tokens, rhs.cached_tokens),
+        }
+    }
+}
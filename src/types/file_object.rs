@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// The intended purpose of an uploaded file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FilePurpose {
+    #[serde(rename = "assistants")]
+    Assistants,
+    #[serde(rename = "assistants_output")]
+    AssistantsOutput,
+    #[serde(rename = "batch")]
+    Batch,
+    #[serde(rename = "batch_output")]
+    BatchOutput,
+    #[serde(rename = "fine-tune")]
+    FineTune,
+    #[serde(rename = "fine-tune-results")]
+    FineTuneResults,
+    #[serde(rename = "vision")]
+    Vision,
+    #[serde(rename = "user_data")]
+    UserData,
+    #[serde(rename = "evals")]
+    Evals,
+    /// A purpose unknown to this crate, e.g. of another provider.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl FilePurpose {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FilePurpose::Assistants => "assistants",
+            FilePurpose::AssistantsOutput => "assistants_output",
+            FilePurpose::Batch => "batch",
+            FilePurpose::BatchOutput => "batch_output",
+            FilePurpose::FineTune => "fine-tune",
+            FilePurpose::FineTuneResults => "fine-tune-results",
+            FilePurpose::Vision => "vision",
+            FilePurpose::UserData => "user_data",
+            FilePurpose::Evals => "evals",
+            FilePurpose::Other(purpose) => purpose,
+        }
+    }
+}
+
+/// A file uploaded to the provider.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileObject {
+    /// The file identifier, which can be referenced in the API endpoints.
+    pub id: String,
+
+    /// The object type, which is always `file`.
+    pub object: Option<String>,
+
+    /// The size of the file, in bytes.
+    pub bytes: Option<u64>,
+
+    /// The Unix timestamp (in seconds) for when the file was created.
+    pub created_at: Option<u64>,
+
+    /// The name of the file.
+    pub filename: Option<String>,
+
+    /// The intended purpose of the file.
+    pub purpose: Option<FilePurpose>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileList {
+    pub object: Option<String>,
+    pub data: Vec<FileObject>,
+    pub first_id: Option<String>,
+    pub last_id: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeletedFile {
+    pub id: String,
+    pub object: Option<String>,
+    pub deleted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_purpose_works() {
+        let purposes: Vec<FilePurpose> =
+            serde_json::from_str(r#"["fine-tune", "batch_output", "custom"]"#).unwrap();
+        assert_eq!(
+            purposes,
+            vec![
+                FilePurpose::FineTune,
+                FilePurpose::BatchOutput,
+                FilePurpose::Other("custom".into())
+            ]
+        );
+        for purpose in purposes {
+            assert_eq!(
+                serde_json::to_value(&purpose).unwrap().as_str(),
+                Some(purpose.as_str())
+            );
+        }
+    }
+}
@@ -12,6 +12,7 @@ pub mod chat_tool_choice;
 pub mod completion_choice;
 pub mod completion_usage;
 pub mod content;
+pub mod file_object;
 pub mod image_url;
 pub mod input_audio;
 pub mod modalities;
@@ -36,6 +37,7 @@ pub use chat_tool_choice::*;
 pub use completion_choice::*;
 pub use completion_usage::*;
 pub use content::*;
+pub use file_object::*;
 pub use image_url::*;
 pub use input_audio::*;
 pub use modalities::*;
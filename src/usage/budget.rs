@@ -0,0 +1,368 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{error::Error, types::CompletionUsage};
+
+use super::{is_model, PricingTable};
+
+/// The quantity a [`Budget`] limits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetLimit {
+    /// Total tokens (prompt + completion).
+    Tokens(u64),
+    /// Cost in USD, computed with the pricing table of [`Budgets`].
+    Cost(f64),
+}
+
+/// The requests a [`Budget`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetScope {
+    /// Every request of the client.
+    Client,
+    /// Requests tagged with [`crate::chat::Chat::with_tag`].
+    Tag(String),
+    /// Requests to a model, including its dated snapshots (`gpt-4o-mini` covers `gpt-4o-mini-2024-07-18`) and provider prefixed names (`openai/gpt-4o-mini`).
+    Model(String),
+}
+
+/// A spend limit. Hard budgets reject requests once exceeded, soft budgets only notify the callback of [`Budgets`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Budget {
+    pub limit: BudgetLimit,
+    pub scope: BudgetScope,
+    /// Only count the usage of this sliding time window. `None` counts all usage.
+    pub window: Option<Duration>,
+    pub hard: bool,
+}
+
+impl Budget {
+    pub fn hard(limit: BudgetLimit) -> Self {
+        Self {
+            limit,
+            scope: BudgetScope::Client,
+            window: None,
+            hard: true,
+        }
+    }
+
+    pub fn soft(limit: BudgetLimit) -> Self {
+        Self {
+            hard: false,
+            ..Self::hard(limit)
+        }
+    }
+
+    pub fn for_tag(mut self, tag: impl Into<String>) -> Self {
+        self.scope = BudgetScope::Tag(tag.into());
+        self
+    }
+
+    pub fn for_model(mut self, model: impl Into<String>) -> Self {
+        self.scope = BudgetScope::Model(model.into());
+        self
+    }
+
+    pub fn per(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    fn applies_to(&self, model: Option<&str>, tags: &[String]) -> bool {
+        match &self.scope {
+            BudgetScope::Client => true,
+            BudgetScope::Tag(tag) => tags.contains(tag),
+            BudgetScope::Model(name) => model.is_none_or(|model| is_model(name, model)),
+        }
+    }
+}
+
+/// Passed to the soft limit callback when a soft budget is crossed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetEvent {
+    pub budget: Budget,
+    /// The tokens or USD spent in the budget's window, including the request that crossed the limit.
+    pub spent: f64,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    budget: Budget,
+    entries: VecDeque<(Instant, u64, f64)>,
+    tokens: u64,
+    cost: f64,
+}
+
+impl BudgetState {
+    fn prune(&mut self, now: Instant) {
+        let Some(window) = self.budget.window else {
+            return;
+        };
+        while let Some((at, tokens, cost)) = self.entries.front().copied() {
+            if now.duration_since(at) < window {
+                break;
+            }
+            self.entries.pop_front();
+            self.tokens -= tokens;
+            self.cost -= cost;
+        }
+    }
+
+    fn spent(&self) -> f64 {
+        match self.budget.limit {
+            BudgetLimit::Tokens(_) => self.tokens as f64,
+            BudgetLimit::Cost(_) => self.cost,
+        }
+    }
+
+    fn limit(&self) -> f64 {
+        match self.budget.limit {
+            BudgetLimit::Tokens(limit) => limit as f64,
+            BudgetLimit::Cost(limit) => limit,
+        }
+    }
+}
+
+type SoftLimitCallback = Arc<dyn Fn(&BudgetEvent) + Send + Sync>;
+
+/// A set of budgets enforced by a client. Attach it with [`crate::Client::with_budgets`]; clones share the same spend.
+#[derive(Clone)]
+pub struct Budgets {
+    pricing: Arc<PricingTable>,
+    states: Arc<Mutex<Vec<BudgetState>>>,
+    on_soft_limit: Option<SoftLimitCallback>,
+}
+
+impl Debug for Budgets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Budgets")
+            .field("budgets", &self.budgets())
+            .finish()
+    }
+}
+
+impl Default for Budgets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Budgets {
+    pub fn new() -> Self {
+        Self {
+            pricing: Default::default(),
+            states: Default::default(),
+            on_soft_limit: None,
+        }
+    }
+
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = Arc::new(pricing);
+        self
+    }
+
+    /// Adds a budget. Like the spend, budgets are shared with every clone, including the one attached to a client.
+    pub fn add_budget(&self, budget: Budget) {
+        self.lock().push(BudgetState {
+            budget,
+            entries: VecDeque::new(),
+            tokens: 0,
+            cost: 0.0,
+        });
+    }
+
+    /// Adds a budget, see [`Budgets::add_budget`]: calling it on a clone adds the budget to every clone as well.
+    pub fn with_budget(self, budget: Budget) -> Self {
+        self.add_budget(budget);
+        self
+    }
+
+    /// Called whenever a request makes a soft budget go over its limit.
+    pub fn on_soft_limit(
+        mut self,
+        callback: impl Fn(&BudgetEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_soft_limit = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn budgets(&self) -> Vec<Budget> {
+        self.lock()
+            .iter()
+            .map(|state| state.budget.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<BudgetState>> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fails with [`Error::BudgetExceeded`] if a hard budget that applies to the request is already used up.
+    ///
+    /// A cost budget cannot be enforced for a model without a price: hard budgets fail with [`Error::InvalidConfig`], soft budgets log a warning.
+    pub fn check(&self, model: Option<&str>, tags: &[String]) -> Result<(), Error> {
+        let now = Instant::now();
+        let mut states = self.lock();
+        for state in states.iter_mut() {
+            if !state.budget.applies_to(model, tags) {
+                continue;
+            }
+            let unpriced = model.filter(|model| self.pricing.get(model).is_none());
+            if let (BudgetLimit::Cost(_), Some(model)) = (state.budget.limit, unpriced) {
+                if state.budget.hard {
+                    return Err(Error::InvalidConfig(format!(
+                        "{:?} has a cost budget but model {model} has no price, add it with Budgets::with_pricing",
+                        state.budget.scope
+                    )));
+                }
+                tracing::warn!(
+                    "{:?} has a cost budget but model {model} has no price",
+                    state.budget.scope
+                );
+            }
+            if !state.budget.hard {
+                continue;
+            }
+            state.prune(now);
+            if state.spent() >= state.limit() {
+                return Err(Error::BudgetExceeded(format!(
+                    "{:?} spent {} of {:?}",
+                    state.budget.scope,
+                    state.spent(),
+                    state.budget.limit
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the usage of a request to every budget that applies to it.
+    pub fn record(&self, model: &str, tags: &[String], usage: &CompletionUsage) {
+        let now = Instant::now();
+        let tokens = usage.total_tokens.map(u64::from).unwrap_or_else(|| {
+            u64::from(usage.prompt_tokens.unwrap_or(0))
+                + u64::from(usage.completion_tokens.unwrap_or(0))
+        });
+        let cost = self.pricing.cost(model, usage).unwrap_or(0.0);
+
+        let mut events = vec![];
+        {
+            let mut states = self.lock();
+            for state in states.iter_mut() {
+                if !state.budget.applies_to(Some(model), tags) {
+                    continue;
+                }
+                state.prune(now);
+                let before = state.spent();
+                if state.budget.window.is_some() {
+                    state.entries.push_back((now, tokens, cost));
+                }
+                state.tokens += tokens;
+                state.cost += cost;
+                let limit = state.limit();
+                if !state.budget.hard && before < limit && state.spent() >= limit {
+                    events.push(BudgetEvent {
+                        budget: state.budget.clone(),
+                        spent: state.spent(),
+                    });
+                }
+            }
+        }
+        // Run the callback without holding the lock, so it may inspect the budgets.
+        if let Some(callback) = &self.on_soft_limit {
+            events.iter().for_each(|event| callback(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::usage::ModelPricing;
+
+    fn usage(tokens: u32) -> CompletionUsage {
+        CompletionUsage {
+            total_tokens: Some(tokens),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn budgets_work() {
+        let fired = Arc::new(AtomicUsize::new(0));
+        let counter = fired.clone();
+        let budgets = Budgets::new()
+            .with_budget(Budget::hard(BudgetLimit::Tokens(100)).for_tag("agent"))
+            .with_budget(Budget::soft(BudgetLimit::Tokens(50)))
+            .on_soft_limit(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        let tags = vec!["agent".to_string()];
+
+        budgets.record("gpt-4o-mini", &tags, &usage(60));
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+        assert!(budgets.check(None, &tags).is_ok());
+
+        budgets.record("gpt-4o-mini", &tags, &usage(60));
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            budgets.check(None, &tags),
+            Err(Error::BudgetExceeded(_))
+        ));
+        assert!(budgets.check(None, &[]).is_ok());
+    }
+
+    #[test]
+    fn budget_model_scope_works() {
+        let budgets =
+            Budgets::new().with_budget(Budget::hard(BudgetLimit::Tokens(10)).for_model("gpt-4o"));
+        budgets.record("gpt-4o-mini-2024-07-18", &[], &usage(10));
+        assert!(budgets.check(Some("gpt-4o"), &[]).is_ok());
+
+        budgets.record("gpt-4o-2024-08-06", &[], &usage(10));
+        assert!(budgets.check(Some("gpt-4o"), &[]).is_err());
+        assert!(budgets.check(Some("openai/gpt-4o"), &[]).is_err());
+        assert!(budgets.check(Some("gpt-4o-mini"), &[]).is_ok());
+
+        // Clones share their budgets.
+        budgets
+            .clone()
+            .add_budget(Budget::hard(BudgetLimit::Tokens(10)).for_model("gpt-4o-mini"));
+        assert_eq!(budgets.budgets().len(), 2);
+        budgets.record("gpt-4o-mini", &[], &usage(10));
+        assert!(budgets.check(Some("gpt-4o-mini"), &[]).is_err());
+    }
+
+    #[test]
+    fn budget_unpriced_model_works() {
+        let budgets = Budgets::new().with_budget(Budget::hard(BudgetLimit::Cost(1.0)));
+        assert!(budgets.check(Some("gpt-4o-mini"), &[]).is_ok());
+        assert!(matches!(
+            budgets.check(Some("meta-llama/llama-3.1-8b-instruct"), &[]),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        let budgets = budgets.with_pricing(PricingTable::empty().with_model(
+            "meta-llama/llama-3.1-8b-instruct",
+            ModelPricing::new(0.02, 0.05),
+        ));
+        assert!(budgets
+            .check(Some("meta-llama/llama-3.1-8b-instruct"), &[])
+            .is_ok());
+    }
+
+    #[test]
+    fn budget_window_works() {
+        let budgets = Budgets::new()
+            .with_budget(Budget::hard(BudgetLimit::Tokens(10)).per(Duration::from_millis(20)));
+        budgets.record("gpt-4o-mini", &[], &usage(10));
+        assert!(budgets.check(None, &[]).is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(budgets.check(None, &[]).is_ok());
+    }
+}
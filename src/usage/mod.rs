@@ -0,0 +1,53 @@
+use crate::{error::Error, types::CompletionUsage};
+
+pub mod budget;
+pub mod pricing;
+pub mod tracker;
+
+pub use budget::{Budget, BudgetEvent, BudgetLimit, BudgetScope, Budgets};
+pub use pricing::{ModelPricing, PricingTable};
+pub use tracker::{UsageReport, UsageSummary, UsageTracker};
+
+/// Whether `model`, e.g. the model of a response, is `name` or one of its dated snapshots such as `gpt-4o-2024-08-06`, with or without a provider prefix. Other models sharing the prefix, e.g. `gpt-4o-mini`, are not.
+pub(crate) fn is_model(name: &str, model: &str) -> bool {
+    if model == name {
+        return true;
+    }
+    let model = model.rsplit('/').next().unwrap_or(model);
+    model == name
+        || model
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Forwards the usage of one call to the usage tracker and the budgets of a client. It is detached from the client so it can be moved into streams.
+#[derive(Debug, Clone)]
+pub(crate) struct UsageRecorder {
+    pub(crate) provider: String,
+    pub(crate) tags: Vec<String>,
+    pub(crate) tracker: Option<UsageTracker>,
+    pub(crate) budgets: Option<Budgets>,
+}
+
+impl UsageRecorder {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.tracker.is_some() || self.budgets.is_some()
+    }
+
+    pub(crate) fn check(&self, model: Option<&str>) -> Result<(), Error> {
+        match &self.budgets {
+            Some(budgets) => budgets.check(model, &self.tags),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn record(&self, model: &str, usage: &CompletionUsage) {
+        if let Some(tracker) = &self.tracker {
+            tracker.record(&self.provider, model, &self.tags, usage);
+        }
+        if let Some(budgets) = &self.budgets {
+            budgets.record(model, &self.tags, usage);
+        }
+    }
+}
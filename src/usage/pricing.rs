@@ -0,0 +1,224 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, types::CompletionUsage};
+
+use super::is_model;
+
+/// Token prices of a model in USD per 1M tokens.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModelPricing {
+    /// Price of text input tokens.
+    pub input: f64,
+
+    /// Price of cached input tokens. Defaults to `input`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+
+    /// Price of text output tokens.
+    pub output: f64,
+
+    /// Price of reasoning tokens. Defaults to `output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<f64>,
+
+    /// Price of audio input tokens. Defaults to `input`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_input: Option<f64>,
+
+    /// Price of audio output tokens. Defaults to `output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_output: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_cached_input(mut self, price: f64) -> Self {
+        self.cached_input = Some(price);
+        self
+    }
+
+    pub fn with_reasoning(mut self, price: f64) -> Self {
+        self.reasoning = Some(price);
+        self
+    }
+
+    pub fn with_audio(mut self, input: f64, output: f64) -> Self {
+        self.audio_input = Some(input);
+        self.audio_output = Some(output);
+        self
+    }
+
+    /// The cost of the usage in USD.
+    ///
+    /// Cached and audio tokens are part of `prompt_tokens`, reasoning and audio tokens are part of `completion_tokens`, so each token is priced exactly once.
+    pub fn cost(&self, usage: &CompletionUsage) -> f64 {
+        let prompt = usage.prompt_tokens.unwrap_or(0) as f64;
+        let completion = usage.completion_tokens.unwrap_or(0) as f64;
+        let prompt_details = usage.prompt_tokens_details.clone().unwrap_or_default();
+        let completion_details = usage.completion_tokens_details.clone().unwrap_or_default();
+        let cached = prompt_details.cached_tokens.unwrap_or(0) as f64;
+        let audio_input = prompt_details.audio_tokens.unwrap_or(0) as f64;
+        let reasoning = completion_details.reasoning_tokens.unwrap_or(0) as f64;
+        let audio_output = completion_details.audio_tokens.unwrap_or(0) as f64;
+
+        let text_input = (prompt - cached - audio_input).max(0.0);
+        let text_output = (completion - reasoning - audio_output).max(0.0);
+
+        (text_input * self.input
+            + cached * self.cached_input.unwrap_or(self.input)
+            + audio_input * self.audio_input.unwrap_or(self.input)
+            + text_output * self.output
+            + reasoning * self.reasoning.unwrap_or(self.output)
+            + audio_output * self.audio_output.unwrap_or(self.output))
+            / 1_000_000.0
+    }
+}
+
+/// Prices per model. Lookups match the exact model name first, then the longest known prefix, so dated snapshots like `gpt-4o-mini-2024-07-18` use the price of `gpt-4o-mini`.
+///
+/// The default table contains the public OpenAI list prices at the time of writing. Override them with [`PricingTable::with_model`] or load a table from a JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct PricingTable {
+    pub models: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    /// A table without any prices.
+    pub fn empty() -> Self {
+        Self {
+            models: HashMap::new(),
+        }
+    }
+
+    /// Loads a table from a JSON object that maps model names to [`ModelPricing`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.models.insert(model.into(), pricing);
+        self
+    }
+
+    /// Adds or overrides the prices of `other`.
+    pub fn merge(mut self, other: PricingTable) -> Self {
+        self.models.extend(other.models);
+        self
+    }
+
+    pub fn get(&self, model: &str) -> Option<&ModelPricing> {
+        if let Some(pricing) = self.models.get(model) {
+            return Some(pricing);
+        }
+        // Provider prefixed names, e.g. `openai/gpt-4o` on OpenRouter.
+        let model = model.rsplit('/').next().unwrap_or(model);
+        self.models.get(model).or_else(|| {
+            self.models
+                .iter()
+                .filter(|(name, _)| is_model(name, model))
+                .max_by_key(|(name, _)| name.len())
+                .map(|(_, pricing)| pricing)
+        })
+    }
+
+    /// The cost of the usage in USD, or `None` if the model has no price.
+    pub fn cost(&self, model: &str, usage: &CompletionUsage) -> Option<f64> {
+        self.get(model).map(|pricing| pricing.cost(usage))
+    }
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self::empty()
+            .with_model(
+                "gpt-4o",
+                ModelPricing::new(2.5, 10.0).with_cached_input(1.25),
+            )
+            .with_model(
+                "gpt-4o-mini",
+                ModelPricing::new(0.15, 0.6).with_cached_input(0.075),
+            )
+            .with_model(
+                "gpt-4o-audio-preview",
+                ModelPricing::new(2.5, 10.0).with_audio(40.0, 80.0),
+            )
+            .with_model(
+                "gpt-4o-mini-audio-preview",
+                ModelPricing::new(0.15, 0.6).with_audio(10.0, 20.0),
+            )
+            .with_model("o1", ModelPricing::new(15.0, 60.0).with_cached_input(7.5))
+            .with_model(
+                "o1-mini",
+                ModelPricing::new(1.1, 4.4).with_cached_input(0.55),
+            )
+            .with_model(
+                "o3-mini",
+                ModelPricing::new(1.1, 4.4).with_cached_input(0.55),
+            )
+            .with_model("gpt-4-turbo", ModelPricing::new(10.0, 30.0))
+            .with_model("gpt-4", ModelPricing::new(30.0, 60.0))
+            .with_model("gpt-3.5-turbo", ModelPricing::new(0.5, 1.5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{response::Respondable, types::PromptTokensDetails, ChatResponseStream};
+
+    use super::*;
+
+    #[test]
+    fn pricing_table_works() {
+        let table = PricingTable::default();
+        assert_eq!(
+            table.get("gpt-4o-mini-2024-07-18"),
+            table.get("gpt-4o-mini")
+        );
+        assert_eq!(table.get("openai/gpt-4o"), table.get("gpt-4o"));
+        assert!(table.get("llama3.2:3b").is_none());
+
+        // Another model sharing the prefix is not a snapshot.
+        let snapshots = PricingTable::empty().with_model("gpt-4o", ModelPricing::new(2.5, 10.0));
+        assert!(snapshots.get("gpt-4o-2024-08-06").is_some());
+        assert!(snapshots.get("gpt-4o-mini").is_none());
+
+        let usage = CompletionUsage {
+            prompt_tokens: Some(1_000_000),
+            completion_tokens: Some(1_000_000),
+            prompt_tokens_details: Some(PromptTokensDetails {
+                audio_tokens: None,
+                cached_tokens: Some(500_000),
+            }),
+            ..Default::default()
+        };
+        let cost = table.cost("gpt-4o", &usage).unwrap();
+        assert!((cost - (1.25 + 0.625 + 10.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stream_usage_pricing_works() {
+        let chunk: ChatResponseStream = serde_json::from_str(
+            r#"{"id":"1","model":"gpt-4o-2024-08-06","choices":[],"usage":{"prompt_tokens":1000000,"completion_tokens":1000000,"total_tokens":2000000,"prompt_tokens_details":{"cached_tokens":500000},"completion_tokens_details":{"reasoning_tokens":0}}}"#,
+        )
+        .unwrap();
+        let usage = chunk.usage().unwrap();
+        assert_eq!(
+            usage.prompt_tokens_details.as_ref().unwrap().cached_tokens,
+            Some(500_000)
+        );
+        let cost = PricingTable::default()
+            .cost(chunk.model().unwrap(), &usage)
+            .unwrap();
+        assert!((cost - (1.25 + 0.625 + 10.0)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::CompletionUsage;
+
+use super::PricingTable;
+
+/// Aggregated usage and cost of a group of requests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageSummary {
+    /// The number of recorded requests.
+    pub requests: u64,
+
+    /// The sum of the token usage of all requests.
+    pub usage: CompletionUsage,
+
+    /// The cost in USD.
+    pub cost: f64,
+
+    /// The number of requests whose model has no price. Their tokens are counted but add nothing to `cost`.
+    pub unpriced_requests: u64,
+}
+
+impl UsageSummary {
+    fn add(&mut self, usage: &CompletionUsage, cost: Option<f64>) {
+        self.requests += 1;
+        self.usage += usage.clone();
+        match cost {
+            Some(cost) => self.cost += cost,
+            None => self.unpriced_requests += 1,
+        }
+    }
+}
+
+/// A snapshot of everything recorded by a [`UsageTracker`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageReport {
+    pub total: UsageSummary,
+    pub by_model: HashMap<String, UsageSummary>,
+    pub by_provider: HashMap<String, UsageSummary>,
+    pub by_tag: HashMap<String, UsageSummary>,
+}
+
+/// Aggregates token usage and cost across calls. Attach it to a client with [`crate::Client::with_usage_tracker`]; clones share the same totals.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    pricing: Arc<PricingTable>,
+    report: Arc<Mutex<UsageReport>>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pricing(pricing: PricingTable) -> Self {
+        Self {
+            pricing: Arc::new(pricing),
+            report: Default::default(),
+        }
+    }
+
+    pub fn pricing(&self) -> &PricingTable {
+        &self.pricing
+    }
+
+    /// Records the usage of one request and returns its cost, or `None` if the model has no price.
+    pub fn record(
+        &self,
+        provider: &str,
+        model: &str,
+        tags: &[String],
+        usage: &CompletionUsage,
+    ) -> Option<f64> {
+        let cost = self.pricing.cost(model, usage);
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        report.total.add(usage, cost);
+        report
+            .by_model
+            .entry(model.to_string())
+            .or_default()
+            .add(usage, cost);
+        report
+            .by_provider
+            .entry(provider.to_string())
+            .or_default()
+            .add(usage, cost);
+        for tag in tags {
+            report
+                .by_tag
+                .entry(tag.clone())
+                .or_default()
+                .add(usage, cost);
+        }
+        cost
+    }
+
+    pub fn report(&self) -> UsageReport {
+        self.report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn total(&self) -> UsageSummary {
+        self.report().total
+    }
+
+    /// The total cost in USD.
+    pub fn cost(&self) -> f64 {
+        self.total().cost
+    }
+
+    pub fn reset(&self) {
+        *self.report.lock().unwrap_or_else(|e| e.into_inner()) = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_tracker_works() {
+        let tracker = UsageTracker::new();
+        let usage = CompletionUsage {
+            prompt_tokens: Some(10),
+            completion_tokens: Some(5),
+            total_tokens: Some(15),
+            ..Default::default()
+        };
+        let tags = vec!["classification".to_string()];
+        assert!(tracker
+            .record("api.openai.com", "gpt-4o-mini", &tags, &usage)
+            .is_some());
+        assert!(tracker
+            .clone()
+            .record("localhost:11434", "llama3.2:3b", &[], &usage)
+            .is_none());
+
+        let report = tracker.report();
+        assert_eq!(report.total.requests, 2);
+        assert_eq!(report.total.unpriced_requests, 1);
+        assert_eq!(report.total.usage.total_tokens, Some(30));
+        assert_eq!(report.by_tag["classification"].requests, 1);
+        assert_eq!(report.by_provider["localhost:11434"].cost, 0.0);
+    }
+}